@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use crate::net::SendRetrying;
+
+/// Refresh the installation token this long before it expires
+const REFRESH_MARGIN_MINUTES: i64 = 5;
+
+/// GitHub App credentials from `--app-id`, `--app-key` and `--installation-id`
+pub struct AppCredentials {
+    pub app_id: String,
+    pub key_path: PathBuf,
+    pub installation_id: u64,
+}
+
+struct AppAuth {
+    credentials: AppCredentials,
+    key: jsonwebtoken::EncodingKey,
+    token: Mutex<Option<InstallationToken>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct InstallationToken {
+    token: String,
+    /// An hour after minting
+    expires_at: DateTime<Utc>,
+}
+
+/// Claims of the JWT the app authenticates with
+#[derive(Serialize)]
+struct Claims {
+    iat: i64,
+    exp: i64,
+    iss: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    message: String,
+}
+
+static APP: OnceLock<AppAuth> = OnceLock::new();
+
+/// Authenticate as an installation of the app from now on
+pub fn configure(credentials: AppCredentials) -> Result<()> {
+    let pem = std::fs::read(&credentials.key_path)
+        .context(format!("Failed to read the app's private key {}", credentials.key_path.display()))?;
+    let key = jsonwebtoken::EncodingKey::from_rsa_pem(&pem)
+        .map_err(|e| anyhow::anyhow!("Invalid private key {}: {}", credentials.key_path.display(), e))?;
+    let _ = APP.set(AppAuth { credentials, key, token: Mutex::new(None) });
+    Ok(())
+}
+
+/// The current installation token, if running as an app
+pub fn current_token() -> Option<String> {
+    let app = APP.get()?;
+    app.token.lock().unwrap().as_ref().map(|t| t.token.clone())
+}
+
+fn needs_refresh(app: &AppAuth) -> bool {
+    app.token.lock().unwrap().as_ref()
+        .is_none_or(|t| t.expires_at - Utc::now() < Duration::minutes(REFRESH_MARGIN_MINUTES))
+}
+
+/// Exchange a JWT signed with the app's key for an installation token
+async fn mint(app: &AppAuth) -> Result<InstallationToken> {
+    // Backdated a minute against clock drift; GitHub allows at most ten minutes
+    let now = Utc::now().timestamp();
+    let claims = Claims { iat: now - 60, exp: now + 540, iss: app.credentials.app_id.clone() };
+    let jwt = jsonwebtoken::encode(&jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256), &claims, &app.key)
+        .context("Failed to sign the app's JWT")?;
+
+    let url = format!("{}/app/installations/{}/access_tokens", crate::config::api_base(), app.credentials.installation_id);
+    let response = crate::net::client()?
+        .post(&url)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .header(reqwest::header::USER_AGENT, crate::net::USER_AGENT)
+        .bearer_auth(jwt)
+        .send_retrying()
+        .await
+        .context("Failed to request an installation token")?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<ErrorResponse>(&body).map_or(body, |e| e.message);
+        anyhow::bail!("Failed to get an installation token ({}): {}", status, message);
+    }
+    response.json().await.context("Failed to parse the installation token response")
+}
+
+/// Mint a token if there's none yet or the current one is about to expire
+pub async fn refresh_if_needed() -> Result<()> {
+    let Some(app) = APP.get() else {
+        return Ok(());
+    };
+    if needs_refresh(app) {
+        let token = mint(app).await?;
+        *app.token.lock().unwrap() = Some(token);
+    }
+    Ok(())
+}
+
+/// Keep the token fresh for runs that outlast it, e.g. cloning for git history
+/// or `watch`. Requests made after a refresh use the new token.
+pub async fn keep_fresh() {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    loop {
+        let expires_at = app.token.lock().unwrap().as_ref().map(|t| t.expires_at);
+        let wait = expires_at
+            .map(|at| (at - Utc::now() - Duration::minutes(REFRESH_MARGIN_MINUTES)).to_std().unwrap_or_default())
+            .unwrap_or_default()
+            // After a failure, try again in a minute rather than spinning
+            .max(std::time::Duration::from_secs(60));
+        tokio::time::sleep(wait).await;
+        if let Err(e) = refresh_if_needed().await {
+            warn!("Warning: {:#}", e);
+        }
+    }
+}
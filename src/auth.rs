@@ -0,0 +1,584 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::net::SendRetrying;
+use crate::output;
+
+/// Scopes requested at login: private repository events, organization
+/// membership and email addresses for matching git history
+const SCOPES: &str = "repo read:org user:email";
+
+const CREDENTIALS_FILE: &str = "token";
+
+/// Environment variables checked for a token, in order
+const TOKEN_VARS: &[&str] = &["GH_TOKEN", "GITHUB_TOKEN"];
+
+static GH_FALLBACK: AtomicBool = AtomicBool::new(true);
+
+/// The gh CLI's token, looked up at most once per run
+static GH_TOKEN: OnceLock<Option<String>> = OnceLock::new();
+
+/// Token given with `--token`
+static FLAG_TOKEN: OnceLock<String> = OnceLock::new();
+
+/// Whether the token can read private repositories, as far as the first
+/// response to it tells: `None` when it doesn't list scopes
+static REPO_SCOPE: OnceLock<Option<bool>> = OnceLock::new();
+
+/// Whether a rejected token falls back to public data, from `--allow-anonymous`
+static ALLOW_ANONYMOUS: AtomicBool = AtomicBool::new(false);
+
+/// Whether the fallback to public data has been announced
+static WARNED_ANONYMOUS: AtomicBool = AtomicBool::new(false);
+
+#[derive(clap::Args)]
+pub struct AuthArgs {
+    #[command(subcommand)]
+    command: AuthCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum AuthCommand {
+    /// Log in with GitHub's device flow and store the token
+    Login {
+        /// Client ID of the GitHub OAuth app to authorize, with device flow enabled
+        #[arg(long, env = "WIWO_CLIENT_ID")]
+        client_id: String,
+    },
+    /// Show which GitHub account the token in use belongs to
+    Status,
+    /// Remove the stored token
+    Logout,
+    /// Store a token in the OS keychain, prompting for it without echoing
+    #[cfg(feature = "keyring")]
+    SetToken,
+    /// Remove the token from the OS keychain
+    #[cfg(feature = "keyring")]
+    DeleteToken,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceCode {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    /// Seconds until the codes stop working
+    expires_in: u64,
+    /// Minimum seconds between polls of the token endpoint
+    interval: u64,
+}
+
+/// Token endpoint response: a token, or an error such as `authorization_pending`
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+    /// New polling interval, sent with `slow_down`
+    interval: Option<u64>,
+}
+
+fn credentials_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "wiwo").map(|dirs| dirs.config_dir().join(CREDENTIALS_FILE))
+}
+
+/// The token saved by `auth login`, if any
+pub fn stored_token() -> Option<String> {
+    let token = std::fs::read_to_string(credentials_path()?).ok()?;
+    let token = token.trim();
+    (!token.is_empty()).then(|| token.to_string())
+}
+
+/// Whether to fall back to the gh CLI's login, off with `--no-gh-fallback`
+pub fn set_gh_fallback(enabled: bool) {
+    GH_FALLBACK.store(enabled, Ordering::Relaxed);
+}
+
+/// Use `token` ahead of any other, from `--token`
+pub fn set_flag_token(token: &str) {
+    let _ = FLAG_TOKEN.set(token.trim().to_string());
+}
+
+/// Fail early on a mistyped `--token`, which GitHub would otherwise reject on
+/// every request, rather than leaving commands to report confusing errors
+pub async fn verify_flag_token() -> Result<()> {
+    if FLAG_TOKEN.get().is_none() {
+        return Ok(());
+    }
+    let (client, headers) = crate::setup_github_client()?;
+    let response = client
+        .get(format!("{}/user", crate::config::api_base()))
+        .headers(headers.clone())
+        .send_retrying()
+        .await;
+    // Other failures, like being offline, are left to the command to report
+    if let Ok(response) = response
+        && response.status() == reqwest::StatusCode::UNAUTHORIZED
+    {
+        if let Some(authorization) = headers.get(reqwest::header::AUTHORIZATION) {
+            crate::cache::forget_login(authorization.as_bytes());
+        }
+        anyhow::bail!("Authentication failed (401): check --token");
+    }
+    Ok(())
+}
+
+/// GitHub answered 401 or 403 to a request sent with a token, most likely
+/// because it expired or was revoked
+#[derive(Debug)]
+pub struct TokenRejected {
+    pub status: reqwest::StatusCode,
+    /// Where the token came from, as `token()` reports it
+    pub source: String,
+}
+
+impl TokenRejected {
+    pub fn new(status: reqwest::StatusCode) -> Self {
+        let source = token().map_or_else(|| "GH_TOKEN".to_string(), |(_, source)| source);
+        TokenRejected { status, source }
+    }
+}
+
+impl std::fmt::Display for TokenRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GitHub rejected the provided token ({}). Check {}.", self.status.as_u16(), self.source)
+    }
+}
+
+impl std::error::Error for TokenRejected {}
+
+/// Whether to carry on without a token GitHub rejected, from `--allow-anonymous`
+pub fn set_allow_anonymous(enabled: bool) {
+    ALLOW_ANONYMOUS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn allow_anonymous() -> bool {
+    ALLOW_ANONYMOUS.load(Ordering::Relaxed)
+}
+
+/// Say once per run that only public data follows, however many requests
+/// find the token rejected
+pub fn warn_anonymous(rejected: &TokenRejected) {
+    if !WARNED_ANONYMOUS.swap(true, Ordering::Relaxed) {
+        warn!("Warning: {} Continuing without it, so only public events are shown.", rejected);
+    }
+}
+
+/// Note the token's scopes from a response to a request sent with it, and
+/// warn once if they don't cover private repositories. Only classic tokens
+/// list theirs in `X-OAuth-Scopes`; fine-grained and app tokens don't.
+pub fn record_scopes(response_headers: &reqwest::header::HeaderMap) {
+    REPO_SCOPE.get_or_init(|| {
+        let scopes = response_headers.get("x-oauth-scopes")?.to_str().ok()?;
+        let repo = scopes.split(',').any(|scope| scope.trim() == "repo");
+        if !repo {
+            warn!("Warning: The token lacks the repo scope; private repositories may be missing or shown with Unknown visibility");
+        }
+        Some(repo)
+    });
+}
+
+/// Whether the token is known to read private repositories
+pub fn has_repo_scope() -> bool {
+    REPO_SCOPE.get().copied().flatten() == Some(true)
+}
+
+/// A token from `GH_TOKEN` or `GITHUB_TOKEN`, with the variable's name
+pub fn env_token() -> Option<(String, String)> {
+    env_token_from(&System)
+}
+
+fn env_token_from(sources: &impl TokenSources) -> Option<(String, String)> {
+    TOKEN_VARS.iter().find_map(|var| {
+        sources.env(var)
+            .filter(|token| !token.trim().is_empty())
+            .map(|token| (token.trim().to_string(), var.to_string()))
+    })
+}
+
+/// Where `token` looks for one, so tests can stand in for the environment,
+/// config files and the gh CLI
+trait TokenSources {
+    fn app(&self) -> Option<String>;
+    fn flag(&self) -> Option<String>;
+    fn env(&self, var: &str) -> Option<String>;
+    fn keychain(&self) -> Option<String>;
+    /// With the file it came from
+    fn netrc(&self) -> Option<(String, PathBuf)>;
+    /// The token stored by `auth login`, with its file
+    fn stored(&self) -> Option<(String, PathBuf)>;
+    /// `None` when the fallback is off
+    fn gh(&self) -> Option<String>;
+}
+
+/// The real sources
+struct System;
+
+impl TokenSources for System {
+    fn app(&self) -> Option<String> {
+        crate::app::current_token()
+    }
+
+    fn flag(&self) -> Option<String> {
+        FLAG_TOKEN.get().filter(|token| !token.is_empty()).cloned()
+    }
+
+    fn env(&self, var: &str) -> Option<String> {
+        std::env::var(var).ok()
+    }
+
+    fn keychain(&self) -> Option<String> {
+        #[cfg(feature = "keyring")]
+        return crate::keychain::stored_token();
+        #[cfg(not(feature = "keyring"))]
+        None
+    }
+
+    fn netrc(&self) -> Option<(String, PathBuf)> {
+        crate::netrc::token()
+    }
+
+    fn stored(&self) -> Option<(String, PathBuf)> {
+        Some((stored_token()?, credentials_path().unwrap_or_default()))
+    }
+
+    fn gh(&self) -> Option<String> {
+        if !GH_FALLBACK.load(Ordering::Relaxed) {
+            return None;
+        }
+        GH_TOKEN.get_or_init(gh_token).clone()
+    }
+}
+
+/// The token to use and where it came from: a GitHub App's installation
+/// token, `--token`, `GH_TOKEN` or `GITHUB_TOKEN`, the keychain's, the one
+/// in `~/.netrc`, then the one stored by `auth login`, then the gh CLI's
+pub fn token() -> Option<(String, String)> {
+    token_from(&System)
+}
+
+fn token_from(sources: &impl TokenSources) -> Option<(String, String)> {
+    if let Some(token) = sources.app() {
+        return Some((token, "the GitHub App installation".to_string()));
+    }
+    if let Some(token) = sources.flag() {
+        return Some((token, "--token".to_string()));
+    }
+    if let Some(found) = env_token_from(sources) {
+        return Some(found);
+    }
+    if let Some(token) = sources.keychain() {
+        return Some((token, "the OS keychain".to_string()));
+    }
+    if let Some((token, path)) = sources.netrc() {
+        return Some((token, path.display().to_string()));
+    }
+    if let Some((token, path)) = sources.stored() {
+        return Some((token, path.display().to_string()));
+    }
+    sources.gh().map(|token| (token, "the gh CLI".to_string()))
+}
+
+/// Where gh keeps its config: `$GH_CONFIG_DIR`, else `$XDG_CONFIG_HOME/gh`,
+/// else `~/.config/gh` (also on macOS), or `%AppData%\GitHub CLI` on Windows
+fn gh_config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("GH_CONFIG_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("gh"));
+    }
+    let dirs = directories::BaseDirs::new()?;
+    if cfg!(windows) {
+        Some(dirs.config_dir().join("GitHub CLI"))
+    } else {
+        Some(dirs.home_dir().join(".config").join("gh"))
+    }
+}
+
+/// `oauth_token` of the API host's entry in gh's `hosts.yml`, e.g. for github.com:
+///
+/// ```yaml
+/// github.com:
+///     oauth_token: gho_...
+///     user: octocat
+/// ```
+///
+/// Only this shape is understood, which is what gh writes. Newer versions keep
+/// the token in the system keyring instead, leaving it out of the file.
+fn hosts_file_token(contents: &str) -> Option<String> {
+    let mut in_github = false;
+    for line in contents.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            in_github = line.trim_end().trim_end_matches(':').trim_matches(['"', '\'']) == crate::config::web_host();
+            continue;
+        }
+        // Only keys directly under the host, not those of its `users:` map
+        if in_github
+            && let Some(value) = line.trim().strip_prefix("oauth_token:")
+        {
+            let token = value.trim().trim_matches(['"', '\'']);
+            return (!token.is_empty()).then(|| token.to_string());
+        }
+    }
+    None
+}
+
+/// The gh CLI's token for the API host, from its hosts file or else `gh auth token`.
+/// Any failure leaves the run unauthenticated rather than ending it.
+fn gh_token() -> Option<String> {
+    // Set when the hosts file mentions the host without a token we can read
+    let mut unreadable: Option<String> = None;
+    if let Some(path) = gh_config_dir().map(|dir| dir.join("hosts.yml")) {
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => match hosts_file_token(&contents) {
+                Some(token) => return Some(token),
+                None if contents.contains(crate::config::web_host()) => unreadable = Some(path.display().to_string()),
+                None => {}
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => unreadable = Some(format!("{} ({})", path.display(), e)),
+        }
+    }
+
+    match gh_auth_token() {
+        Some(token) => Some(token),
+        None => {
+            if let Some(file) = unreadable {
+                warn!("Warning: Couldn't get a token from the gh CLI's login in {}; continuing unauthenticated", file);
+            }
+            None
+        }
+    }
+}
+
+/// Output of `gh auth token`, which also reads tokens from the keyring.
+/// `None` if gh isn't installed or isn't logged in.
+fn gh_auth_token() -> Option<String> {
+    let output = std::process::Command::new("gh")
+        .args(["auth", "token", "--hostname", crate::config::web_host()])
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let token = String::from_utf8(output.stdout).ok()?;
+    let token = token.trim();
+    (!token.is_empty()).then(|| token.to_string())
+}
+
+fn store_token(token: &str) -> Result<PathBuf> {
+    let path = credentials_path().context("Could not determine a config directory (no home directory)")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .context(format!("Failed to create {}", parent.display()))?;
+    }
+    output::write_private(&path, token)?;
+    Ok(path)
+}
+
+/// Run the device flow: show the user a code to enter on github.com, then
+/// poll until they've authorized the app
+async fn login(client_id: &str) -> Result<String> {
+    let client = crate::net::client()?;
+    let code: DeviceCode = client
+        .post(format!("{}/login/device/code", crate::config::web_base()))
+        .header(reqwest::header::ACCEPT, "application/json")
+        .form(&[("client_id", client_id), ("scope", SCOPES)])
+        .send()
+        .await
+        .context("Failed to start the device flow")?
+        .error_for_status()
+        .context("Failed to start the device flow")?
+        .json()
+        .await
+        .context("Failed to parse the device code response")?;
+
+    println!("Open {} and enter the code {}", code.verification_uri, code.user_code);
+    println!("Waiting for authorization...");
+
+    let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(code.expires_in);
+    let mut interval = code.interval;
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+        if tokio::time::Instant::now() >= deadline {
+            anyhow::bail!("The code expired before it was entered; run `wiwo auth login` again");
+        }
+
+        let response: TokenResponse = client
+            .post(format!("{}/login/oauth/access_token", crate::config::web_base()))
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&[
+                ("client_id", client_id),
+                ("device_code", code.device_code.as_str()),
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+            ])
+            .send()
+            .await
+            .context("Failed to poll for the access token")?
+            .json()
+            .await
+            .context("Failed to parse the access token response")?;
+
+        if let Some(token) = response.access_token {
+            return Ok(token);
+        }
+        match response.error.as_deref() {
+            Some("authorization_pending") => {}
+            Some("slow_down") => interval = response.interval.unwrap_or(interval + 5),
+            Some("expired_token") => anyhow::bail!("The code expired before it was entered; run `wiwo auth login` again"),
+            Some("access_denied") => anyhow::bail!("Authorization was denied"),
+            Some(error) => anyhow::bail!("Login failed: {}", response.error_description.as_deref().unwrap_or(error)),
+            None => anyhow::bail!("Login failed: the token endpoint returned neither a token nor an error"),
+        }
+    }
+}
+
+pub async fn run(args: &AuthArgs) -> Result<()> {
+    match &args.command {
+        AuthCommand::Login { client_id } => {
+            let token = login(client_id).await?;
+            let (client, mut headers) = crate::setup_github_client()?;
+            headers.insert(reqwest::header::AUTHORIZATION, format!("Bearer {}", token).parse()
+                .context("Invalid GitHub token format")?);
+            let Some(login) = crate::verify_authenticated_user(&client, &headers).await? else {
+                anyhow::bail!("GitHub didn't accept the new token");
+            };
+            let path = store_token(&token)?;
+            println!("Logged in as {}; token stored in {}", login, path.display());
+            if let Some((_, var)) = env_token() {
+                warn!("Note: {} is set and takes precedence over the stored token", var);
+            }
+        }
+        AuthCommand::Status => {
+            let Some((_, source)) = token() else {
+                println!("Not logged in; run `wiwo auth login` or set GH_TOKEN");
+                return Ok(());
+            };
+            let (client, headers) = crate::setup_github_client()?;
+            match crate::verify_authenticated_user(&client, &headers).await? {
+                Some(login) => println!("Logged in as {} (token from {})", login, source),
+                None => anyhow::bail!("The token from {} is invalid or has expired", source),
+            }
+        }
+        AuthCommand::Logout => {
+            match credentials_path().filter(|path| path.exists()) {
+                Some(path) => {
+                    std::fs::remove_file(&path)
+                        .context(format!("Failed to remove {}", path.display()))?;
+                    println!("Removed the stored token from {}", path.display());
+                }
+                None => println!("No stored token to remove"),
+            }
+            if let Some((_, var)) = env_token() {
+                warn!("Note: {} is still set in the environment", var);
+            }
+        }
+        #[cfg(feature = "keyring")]
+        AuthCommand::SetToken => crate::keychain::set_token().await?,
+        #[cfg(feature = "keyring")]
+        AuthCommand::DeleteToken => crate::keychain::delete_token().await?,
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stand-ins for each source the token can come from
+    #[derive(Default)]
+    struct Fake {
+        app: Option<&'static str>,
+        flag: Option<&'static str>,
+        gh_token_var: Option<&'static str>,
+        github_token_var: Option<&'static str>,
+        keychain: Option<&'static str>,
+        netrc: Option<&'static str>,
+        stored: Option<&'static str>,
+        gh: Option<&'static str>,
+    }
+
+    impl TokenSources for Fake {
+        fn app(&self) -> Option<String> {
+            self.app.map(str::to_string)
+        }
+
+        fn flag(&self) -> Option<String> {
+            self.flag.map(str::to_string)
+        }
+
+        fn env(&self, var: &str) -> Option<String> {
+            match var {
+                "GH_TOKEN" => self.gh_token_var,
+                "GITHUB_TOKEN" => self.github_token_var,
+                _ => None,
+            }.map(str::to_string)
+        }
+
+        fn keychain(&self) -> Option<String> {
+            self.keychain.map(str::to_string)
+        }
+
+        fn netrc(&self) -> Option<(String, PathBuf)> {
+            self.netrc.map(|token| (token.to_string(), PathBuf::from("/home/me/.netrc")))
+        }
+
+        fn stored(&self) -> Option<(String, PathBuf)> {
+            self.stored.map(|token| (token.to_string(), PathBuf::from("/home/me/.config/wiwo/token")))
+        }
+
+        fn gh(&self) -> Option<String> {
+            self.gh.map(str::to_string)
+        }
+    }
+
+    fn found(token: &str, source: &str) -> Option<(String, String)> {
+        Some((token.to_string(), source.to_string()))
+    }
+
+    #[test]
+    fn each_source_wins_over_the_later_ones() {
+        let mut sources = Fake {
+            app: Some("app"),
+            flag: Some("flag"),
+            gh_token_var: Some("gh-var"),
+            github_token_var: Some("github-var"),
+            keychain: Some("keychain"),
+            netrc: Some("netrc"),
+            stored: Some("stored"),
+            gh: Some("gh"),
+        };
+        assert_eq!(token_from(&sources), found("app", "the GitHub App installation"));
+        sources.app = None;
+        assert_eq!(token_from(&sources), found("flag", "--token"));
+        sources.flag = None;
+        assert_eq!(token_from(&sources), found("gh-var", "GH_TOKEN"));
+        sources.gh_token_var = None;
+        assert_eq!(token_from(&sources), found("github-var", "GITHUB_TOKEN"));
+        sources.github_token_var = None;
+        assert_eq!(token_from(&sources), found("keychain", "the OS keychain"));
+        sources.keychain = None;
+        assert_eq!(token_from(&sources), found("netrc", "/home/me/.netrc"));
+        sources.netrc = None;
+        assert_eq!(token_from(&sources), found("stored", "/home/me/.config/wiwo/token"));
+        sources.stored = None;
+        assert_eq!(token_from(&sources), found("gh", "the gh CLI"));
+        sources.gh = None;
+        assert_eq!(token_from(&sources), None);
+    }
+
+    #[test]
+    fn blank_environment_variables_are_skipped() {
+        let sources = Fake { gh_token_var: Some("  "), github_token_var: Some(" token\n"), stored: Some("stored"), ..Fake::default() };
+        assert_eq!(token_from(&sources), found("token", "GITHUB_TOKEN"));
+        let sources = Fake { gh_token_var: Some(""), stored: Some("stored"), ..Fake::default() };
+        assert_eq!(token_from(&sources), found("stored", "/home/me/.config/wiwo/token"));
+    }
+}
@@ -0,0 +1,499 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use crate::output;
+use crate::{Event, RepositoryDetails};
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+static REFRESH: AtomicBool = AtomicBool::new(false);
+
+/// How long looked-up repository details are trusted before asking again,
+/// from `--repo-cache-ttl`
+static REPOSITORY_TTL_HOURS: AtomicU32 = AtomicU32::new(24);
+
+/// How long a repository that wasn't found stays that way before asking
+/// again, since it may only have been renamed or transferred
+const NOT_FOUND_TTL_HOURS: i64 = 1;
+
+/// Most repositories kept on disk; those looked up longest ago go first
+const MAX_REPOSITORIES: usize = 5000;
+
+/// How far back stored events are kept; GitHub doesn't return older ones anyway
+const EVENTS_RETENTION_DAYS: i64 = 90;
+
+/// How long the login a token belongs to is remembered
+const LOGIN_TTL_DAYS: i64 = 7;
+
+const REPOSITORIES_FILE: &str = "repositories.json";
+const LOGINS_FILE: &str = "logins.json";
+const PAGES_DIR: &str = "pages";
+const CLONES_DIR: &str = "repos";
+const EVENTS_DIR: &str = "events";
+
+#[derive(clap::Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    command: CacheCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum CacheCommand {
+    /// Show where the cache is and what it holds
+    Status,
+    /// Delete everything in the cache
+    Clear {
+        /// Only delete the repositories cloned for git history
+        #[arg(long, conflicts_with = "events")]
+        clones: bool,
+        /// Only delete the events stored for incremental fetching
+        #[arg(long)]
+        events: bool,
+    },
+}
+
+/// Turn the on-disk cache off, e.g. for `--no-cache`
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn set_repository_ttl(hours: u32) {
+    REPOSITORY_TTL_HOURS.store(hours, Ordering::Relaxed);
+}
+
+/// Ignore cached events and event pages and fetch them all again, e.g. for
+/// `--refresh`; what's fetched is still written to the cache
+pub fn set_refresh(refresh: bool) {
+    REFRESH.store(refresh, Ordering::Relaxed);
+}
+
+/// The cache directory to read events from, or `None` when refreshing
+fn readable_dir() -> Option<PathBuf> {
+    if REFRESH.load(Ordering::Relaxed) { None } else { enabled_dir() }
+}
+
+fn dir() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "wiwo").map(|dirs| dirs.cache_dir().to_path_buf())
+}
+
+/// The cache directory, or `None` when caching is off or there's no home directory
+fn enabled_dir() -> Option<PathBuf> {
+    if ENABLED.load(Ordering::Relaxed) { dir() } else { None }
+}
+
+/// A response body and the ETag to revalidate it with
+#[derive(Serialize, Deserialize)]
+pub struct CachedPage {
+    pub etag: String,
+    /// The `Link` header, since a 304 may leave it out
+    #[serde(default)]
+    pub link: Option<String>,
+    pub body: String,
+}
+
+/// File name for a URL, readable enough to make sense of in `cache status`
+fn page_path(dir: &Path, url: &str) -> PathBuf {
+    let name: String = url.trim_start_matches(crate::config::api_base()).trim_start_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    dir.join(PAGES_DIR).join(format!("{}.json", name))
+}
+
+pub fn load_page(url: &str) -> Option<CachedPage> {
+    let path = page_path(&readable_dir()?, url);
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Save a response for revalidation next time. The cache is only an
+/// optimization, so failing to write it is silently ignored.
+pub fn store_page(url: &str, etag: &str, link: Option<&str>, body: &str) {
+    let Some(dir) = enabled_dir() else {
+        return;
+    };
+    let path = page_path(&dir, url);
+    let page = CachedPage { etag: etag.to_string(), link: link.map(str::to_string), body: body.to_string() };
+    if let Some(parent) = path.parent()
+        && std::fs::create_dir_all(parent).is_ok()
+        && let Ok(contents) = serde_json::to_string(&page)
+    {
+        // Private activity is kept to the owner
+        let _ = output::write_private(&path, &contents);
+    }
+}
+
+/// Where a repository cloned for git history is kept between runs, e.g.
+/// `repos/octocat/hello-world.git` for `octocat/hello-world`; `None` when
+/// caching is off
+pub fn clone_path(full_name: &str) -> Option<PathBuf> {
+    let (owner, name) = full_name.split_once('/')?;
+    Some(enabled_dir()?.join(CLONES_DIR).join(owner).join(format!("{}.git", name)))
+}
+
+/// Events fetched for a user by earlier runs, so later runs only need the
+/// pages newer than them
+#[derive(Serialize, Deserialize)]
+pub struct StoredEvents {
+    /// How far back the events are complete
+    pub since: DateTime<Utc>,
+    pub events: Vec<Event>,
+}
+
+/// Tokens see different events, e.g. a personal token a user's private ones
+/// and CI's only public ones, so each token's are kept apart, under a hash of
+/// its `Authorization` header
+fn events_path(dir: &Path, username: &str, authorization: Option<&[u8]>) -> PathBuf {
+    let name = username.to_lowercase();
+    let name = match authorization {
+        Some(authorization) => format!("{}-{}", name, &token_key(authorization)[..16]),
+        None => name,
+    };
+    dir.join(EVENTS_DIR).join(format!("{}.json", name))
+}
+
+pub fn load_events(username: &str, authorization: Option<&[u8]>) -> Option<StoredEvents> {
+    let path = events_path(&readable_dir()?, username, authorization);
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Save a user's events, dropping those past `EVENTS_RETENTION_DAYS`
+pub fn store_events(username: &str, authorization: Option<&[u8]>, mut stored: StoredEvents) {
+    let Some(dir) = enabled_dir() else {
+        return;
+    };
+    let oldest = Utc::now() - Duration::days(EVENTS_RETENTION_DAYS);
+    stored.events.retain(|event| event.created_at >= oldest);
+    stored.since = stored.since.max(oldest);
+    let path = events_path(&dir, username, authorization);
+    if let Some(parent) = path.parent()
+        && std::fs::create_dir_all(parent).is_ok()
+        && let Ok(contents) = serde_json::to_string(&stored)
+    {
+        let _ = output::write_private(&path, &contents);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedLogin {
+    fetched_at: DateTime<Utc>,
+    login: String,
+}
+
+/// Tokens are only stored hashed, together with the API they're for
+fn token_key(authorization: &[u8]) -> String {
+    use sha2::Digest;
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(crate::config::api_base().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(authorization);
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn read_logins(dir: &Path) -> BTreeMap<String, CachedLogin> {
+    std::fs::read_to_string(dir.join(LOGINS_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_logins(dir: &Path, logins: &BTreeMap<String, CachedLogin>) {
+    if std::fs::create_dir_all(dir).is_ok() && let Ok(contents) = serde_json::to_string(logins) {
+        let _ = output::write_private(&dir.join(LOGINS_FILE), &contents);
+    }
+}
+
+/// The login an earlier run found the `Authorization` header's token to
+/// belong to, if it's recent enough
+pub fn load_login(authorization: &[u8]) -> Option<String> {
+    let cached = read_logins(&enabled_dir()?).remove(&token_key(authorization))?;
+    (Utc::now() - cached.fetched_at < Duration::days(LOGIN_TTL_DAYS)).then_some(cached.login)
+}
+
+pub fn store_login(authorization: &[u8], login: &str) {
+    let Some(dir) = enabled_dir() else {
+        return;
+    };
+    let now = Utc::now();
+    let mut logins = read_logins(&dir);
+    logins.retain(|_, cached| now - cached.fetched_at < Duration::days(LOGIN_TTL_DAYS));
+    logins.insert(token_key(authorization), CachedLogin { fetched_at: now, login: login.to_string() });
+    write_logins(&dir, &logins);
+}
+
+/// Forget the login of a token GitHub rejected
+pub fn forget_login(authorization: &[u8]) {
+    let Some(dir) = enabled_dir() else {
+        return;
+    };
+    let mut logins = read_logins(&dir);
+    if logins.remove(&token_key(authorization)).is_some() {
+        write_logins(&dir, &logins);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedRepository {
+    fetched_at: DateTime<Utc>,
+    details: RepositoryDetails,
+}
+
+/// The repositories on disk; a corrupt file is started over, as it's only a cache
+fn read_repositories(dir: &Path) -> BTreeMap<String, CachedRepository> {
+    let path = dir.join(REPOSITORIES_FILE);
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return BTreeMap::new();
+    };
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        debug!("Ignoring {}, which can't be read: {}", path.display(), e);
+        BTreeMap::new()
+    })
+}
+
+fn is_fresh(repository: &CachedRepository, now: DateTime<Utc>) -> bool {
+    let ttl = if repository.details.not_found {
+        NOT_FOUND_TTL_HOURS
+    } else {
+        i64::from(REPOSITORY_TTL_HOURS.load(Ordering::Relaxed))
+    };
+    now - repository.fetched_at < Duration::hours(ttl)
+}
+
+/// Repository details looked up by earlier runs that are still fresh, to seed
+/// the in-memory cache with
+pub fn load_repositories() -> HashMap<String, Option<RepositoryDetails>> {
+    let Some(dir) = enabled_dir() else {
+        return HashMap::new();
+    };
+    fresh_repositories(&dir, Utc::now())
+}
+
+fn fresh_repositories(dir: &Path, now: DateTime<Utc>) -> HashMap<String, Option<RepositoryDetails>> {
+    read_repositories(dir).into_iter()
+        .filter(|(_, repository)| is_fresh(repository, now))
+        .map(|(name, repository)| (name, Some(repository.details)))
+        .collect()
+}
+
+/// Save successful lookups, keeping when fresh entries from earlier runs
+/// were fetched so they still expire on time
+pub fn store_repositories(repositories: &HashMap<String, Option<RepositoryDetails>>) {
+    if let Some(dir) = enabled_dir() {
+        write_repositories(&dir, repositories, Utc::now());
+    }
+}
+
+fn write_repositories(dir: &Path, repositories: &HashMap<String, Option<RepositoryDetails>>, now: DateTime<Utc>) {
+    let mut stored = read_repositories(dir);
+    stored.retain(|_, repository| is_fresh(repository, now));
+    for (name, details) in repositories {
+        if let Some(details) = details && !stored.contains_key(name) {
+            stored.insert(name.clone(), CachedRepository { fetched_at: now, details: details.clone() });
+        }
+    }
+    if stored.len() > MAX_REPOSITORIES {
+        // Keep the most recently fetched, by name among those fetched at once
+        let mut by_age: Vec<(DateTime<Utc>, String)> = stored.iter()
+            .map(|(name, repository)| (repository.fetched_at, name.clone()))
+            .collect();
+        by_age.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        for (_, name) in &by_age[MAX_REPOSITORIES..] {
+            stored.remove(name);
+        }
+    }
+    if std::fs::create_dir_all(dir).is_ok() && let Ok(contents) = serde_json::to_string(&stored) {
+        let _ = output::write_private(&dir.join(REPOSITORIES_FILE), &contents);
+    }
+}
+
+/// Number of files under `path` and their total size in bytes
+fn usage(path: &Path) -> (usize, u64) {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return (0, 0);
+    };
+    entries.flatten()
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .fold((0, 0), |(count, size), metadata| (count + 1, size + metadata.len()))
+}
+
+/// Total size in bytes of the files anywhere under `path`
+fn tree_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries.flatten()
+        .filter_map(|entry| Some((entry.path(), entry.file_type().ok()?)))
+        .map(|(path, file_type)| match file_type {
+            t if t.is_dir() => tree_size(&path),
+            t if t.is_file() => std::fs::metadata(&path).map_or(0, |m| m.len()),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Repositories cloned for git history, as `owner/name.git` paths
+fn clones(dir: &Path) -> Vec<PathBuf> {
+    let Ok(owners) = std::fs::read_dir(dir.join(CLONES_DIR)) else {
+        return Vec::new();
+    };
+    owners.flatten()
+        .filter_map(|owner| std::fs::read_dir(owner.path()).ok())
+        .flat_map(|repos| repos.flatten().map(|repo| repo.path()))
+        .collect()
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1048576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        1048576..1073741824 => format!("{:.1} MiB", bytes as f64 / 1048576.0),
+        _ => format!("{:.1} GiB", bytes as f64 / 1073741824.0),
+    }
+}
+
+pub fn run(args: &CacheArgs) -> Result<()> {
+    let dir = dir().context("Could not determine a cache directory (no home directory)")?;
+
+    match args.command {
+        CacheCommand::Status => {
+            println!("Cache directory: {}", dir.display());
+            let (pages, pages_size) = usage(&dir.join(PAGES_DIR));
+            println!("Event pages: {} ({})", pages, format_size(pages_size));
+            let repositories = read_repositories(&dir);
+            let now = Utc::now();
+            let expired = repositories.values().filter(|r| !is_fresh(r, now)).count();
+            let size = std::fs::metadata(dir.join(REPOSITORIES_FILE)).map_or(0, |m| m.len());
+            println!("Repositories: {} ({}), {} expired", repositories.len(), format_size(size), expired);
+            let (users, events_size) = usage(&dir.join(EVENTS_DIR));
+            println!("Stored events: {} {} ({})", users, if users == 1 { "user" } else { "users" }, format_size(events_size));
+            let clones = clones(&dir);
+            println!("Clones: {} ({})", clones.len(), format_size(tree_size(&dir.join(CLONES_DIR))));
+        }
+        CacheCommand::Clear { events: true, .. } => {
+            let events_dir = dir.join(EVENTS_DIR);
+            let (users, size) = usage(&events_dir);
+            if users == 0 {
+                println!("No stored events to remove");
+                return Ok(());
+            }
+            std::fs::remove_dir_all(&events_dir)
+                .context(format!("Failed to remove {}", events_dir.display()))?;
+            println!("Removed the stored events of {} {} ({}) from {}", users, if users == 1 { "user" } else { "users" },
+                format_size(size), events_dir.display());
+        }
+        CacheCommand::Clear { clones: true, .. } => {
+            let clones_dir = dir.join(CLONES_DIR);
+            let count = clones(&dir).len();
+            if count == 0 {
+                println!("No cloned repositories to remove");
+                return Ok(());
+            }
+            let size = tree_size(&clones_dir);
+            std::fs::remove_dir_all(&clones_dir)
+                .context(format!("Failed to remove {}", clones_dir.display()))?;
+            println!("Removed {} cloned {} ({}) from {}", count, if count == 1 { "repository" } else { "repositories" },
+                format_size(size), clones_dir.display());
+        }
+        CacheCommand::Clear { clones: false, events: false } => {
+            if !dir.exists() {
+                println!("Cache is already empty");
+                return Ok(());
+            }
+            let (pages, pages_size) = usage(&dir.join(PAGES_DIR));
+            let (users, events_size) = usage(&dir.join(EVENTS_DIR));
+            let (files, size) = usage(&dir);
+            let (clone_count, clones_size) = (clones(&dir).len(), tree_size(&dir.join(CLONES_DIR)));
+            std::fs::remove_dir_all(&dir)
+                .context(format!("Failed to remove {}", dir.display()))?;
+            let count = pages + users + files;
+            let mut removed = format!("{} {} ({})", count, if count == 1 { "file" } else { "files" },
+                format_size(pages_size + events_size + size));
+            if clone_count > 0 {
+                removed.push_str(&format!(" and {} cloned {} ({})", clone_count,
+                    if clone_count == 1 { "repository" } else { "repositories" }, format_size(clones_size)));
+            }
+            println!("Removed {} from {}", removed, dir.display());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn details(not_found: bool) -> Option<RepositoryDetails> {
+        serde_json::from_value(serde_json::json!({ "private": false, "language": "Rust", "not_found": not_found })).unwrap()
+    }
+
+    #[test]
+    fn repositories_expire_after_their_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetched = Utc::now() - Duration::days(3);
+        let repositories = HashMap::from([
+            ("octocat/hello".to_string(), details(false)),
+            ("octocat/gone".to_string(), details(true)),
+            ("octocat/failed".to_string(), None),
+        ]);
+        write_repositories(dir.path(), &repositories, fetched);
+
+        let loaded = fresh_repositories(dir.path(), fetched + Duration::minutes(30));
+        assert_eq!(loaded.len(), 2);
+        assert!(!loaded.contains_key("octocat/failed"));
+        // Missing repositories are looked up again sooner than found ones
+        let loaded = fresh_repositories(dir.path(), fetched + Duration::hours(2));
+        assert_eq!(loaded.keys().collect::<Vec<_>>(), ["octocat/hello"]);
+        assert!(fresh_repositories(dir.path(), fetched + Duration::hours(24)).is_empty());
+    }
+
+    #[test]
+    fn fresh_entries_keep_when_they_were_fetched() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetched = Utc::now() - Duration::days(3);
+        write_repositories(dir.path(), &HashMap::from([("octocat/hello".to_string(), details(false))]), fetched);
+        // Storing it again later doesn't make it fresh again, and expired ones are dropped
+        write_repositories(dir.path(), &HashMap::from([("octocat/hello".to_string(), details(false))]), fetched + Duration::hours(23));
+        write_repositories(dir.path(), &HashMap::from([("octocat/other".to_string(), details(false))]), fetched + Duration::hours(25));
+        assert_eq!(read_repositories(dir.path()).keys().collect::<Vec<_>>(), ["octocat/other"]);
+    }
+
+    #[test]
+    fn corrupt_cache_files_are_started_over() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(REPOSITORIES_FILE), "{\"octocat/hello\": {\"fetched_at\": ").unwrap();
+        std::fs::write(dir.path().join(LOGINS_FILE), "not json").unwrap();
+        assert!(read_repositories(dir.path()).is_empty());
+        assert!(read_logins(dir.path()).is_empty());
+
+        let now = Utc::now();
+        write_repositories(dir.path(), &HashMap::from([("octocat/hello".to_string(), details(false))]), now);
+        assert_eq!(fresh_repositories(dir.path(), now).keys().collect::<Vec<_>>(), ["octocat/hello"]);
+    }
+
+    #[test]
+    fn repositories_are_capped_even_when_fetched_at_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = Utc::now();
+        let repositories: HashMap<String, Option<RepositoryDetails>> = (0..MAX_REPOSITORIES + 10)
+            .map(|i| (format!("octocat/repo-{:05}", i), details(false)))
+            .collect();
+        write_repositories(dir.path(), &repositories, now);
+        let stored = read_repositories(dir.path());
+        assert_eq!(stored.len(), MAX_REPOSITORIES);
+        assert!(stored.contains_key("octocat/repo-00000"));
+        assert!(!stored.contains_key(&format!("octocat/repo-{:05}", MAX_REPOSITORIES)));
+
+        // Newer lookups push out the oldest
+        write_repositories(dir.path(), &HashMap::from([("octocat/newest".to_string(), details(false))]), now + Duration::minutes(1));
+        let stored = read_repositories(dir.path());
+        assert_eq!(stored.len(), MAX_REPOSITORIES);
+        assert!(stored.contains_key("octocat/newest"));
+        assert!(!stored.contains_key(&format!("octocat/repo-{:05}", MAX_REPOSITORIES - 1)));
+    }
+}
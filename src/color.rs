@@ -0,0 +1,58 @@
+use std::io::IsTerminal;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const BLUE: &str = "\x1b[34m";
+const MAGENTA: &str = "\x1b[35m";
+const CYAN: &str = "\x1b[36m";
+
+/// When to emit ANSI colors
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when writing to a terminal and NO_COLOR is unset
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether to color output that would otherwise go to stdout
+    pub fn enabled(self, to_stdout: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+                to_stdout && !no_color && std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+fn paint(s: &str, code: &str) -> String {
+    format!("{}{}{}", code, s, RESET)
+}
+
+pub fn bold(s: &str) -> String {
+    paint(s, BOLD)
+}
+
+pub fn private(s: &str) -> String {
+    paint(s, RED)
+}
+
+/// Color an event type by category, keyed on `Event::formatted_type`
+pub fn event_type(s: &str, formatted_type: &str) -> String {
+    let code = match formatted_type {
+        t if t.starts_with("PR") => GREEN,
+        t if t.starts_with("Issue") => YELLOW,
+        "Push" => BLUE,
+        "Create" | "Delete" => CYAN,
+        "Release" => MAGENTA,
+        _ => return s.to_string(),
+    };
+    paint(s, code)
+}
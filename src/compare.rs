@@ -0,0 +1,193 @@
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::Event;
+use crate::output::plain_table;
+use crate::summary::{DayCount, Summary, TypeCount};
+use crate::timezone::{self, DisplayZone};
+
+#[derive(clap::Args)]
+pub struct CompareArgs {
+    /// GitHub username to compare; pass exactly twice
+    #[arg(short, long = "user", required = true)]
+    users: Vec<String>,
+    /// Time range to compare (e.g., "30d", "1w", "last-month"); limited to what the Events API returns
+    #[arg(short, long, default_value = "30d", value_parser = crate::parse_time_arg)]
+    time: String,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = CompareFormat::Table)]
+    format: CompareFormat,
+    /// Time zone for deciding where days start: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CompareFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Serialize)]
+struct UserStats {
+    user: String,
+    total: usize,
+    unique_repos: usize,
+    by_type: Vec<TypeCount>,
+    /// Local day with the most events, the earliest on a tie
+    busiest_day: Option<DayCount>,
+    /// Events per repository, for finding the shared ones
+    #[serde(skip)]
+    repos: HashMap<String, usize>,
+}
+
+impl UserStats {
+    fn new(user: &str, events: &[Event], timezone: DisplayZone) -> Self {
+        let summary = Summary::from_events(events, timezone, 0);
+        let mut repos: HashMap<String, usize> = HashMap::new();
+        for event in events {
+            *repos.entry(event.repo.name.clone()).or_default() += 1;
+        }
+        UserStats {
+            user: user.to_string(),
+            total: summary.total,
+            unique_repos: repos.len(),
+            by_type: summary.by_type,
+            busiest_day: summary.busiest_day,
+            repos,
+        }
+    }
+
+    fn type_count(&self, event_type: &str) -> usize {
+        self.by_type.iter().find(|t| t.event_type == event_type).map_or(0, |t| t.count)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SharedRepo {
+    repo: String,
+    /// Events per user, in the order the users were given
+    events: Vec<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct Comparison {
+    users: Vec<UserStats>,
+    /// Repositories every user touched, most combined events first
+    shared_repos: Vec<SharedRepo>,
+}
+
+/// Repositories every user has events in; empty unless there are at least two
+fn shared_repos(users: &[UserStats]) -> Vec<SharedRepo> {
+    let Some((first, rest)) = users.split_first() else {
+        return Vec::new();
+    };
+    if rest.is_empty() {
+        return Vec::new();
+    }
+    let mut shared: Vec<SharedRepo> = first.repos.keys()
+        .filter(|repo| rest.iter().all(|u| u.repos.contains_key(*repo)))
+        .map(|repo| SharedRepo {
+            repo: repo.clone(),
+            events: users.iter().map(|u| u.repos[repo]).collect(),
+        })
+        .collect();
+    shared.sort_by(|a, b| b.events.iter().sum::<usize>().cmp(&a.events.iter().sum())
+        .then_with(|| a.repo.cmp(&b.repo)));
+    shared
+}
+
+fn render_table(comparison: &Comparison, time_range: &str) -> String {
+    let users = &comparison.users;
+    let names: Vec<&str> = users.iter().map(|u| u.user.as_str()).collect();
+
+    let mut out = String::new();
+    writeln!(out, "Comparison for {} ({})\n", names.join(" and "), time_range).unwrap();
+
+    let mut rows: Vec<Vec<String>> = vec![
+        std::iter::once("Total events".to_string()).chain(users.iter().map(|u| u.total.to_string())).collect(),
+        std::iter::once("Repositories".to_string()).chain(users.iter().map(|u| u.unique_repos.to_string())).collect(),
+        std::iter::once("Busiest day".to_string())
+            .chain(users.iter().map(|u| u.busiest_day.as_ref()
+                .map_or_else(|| "-".to_string(), |day| format!("{} ({})", day.date.format("%a %Y-%m-%d"), day.count))))
+            .collect(),
+    ];
+
+    // Every type either user has, most combined events first
+    let mut types: Vec<(&str, usize)> = Vec::new();
+    for count in users.iter().flat_map(|u| &u.by_type) {
+        match types.iter_mut().find(|(t, _)| *t == count.event_type) {
+            Some((_, total)) => *total += count.count,
+            None => types.push((&count.event_type, count.count)),
+        }
+    }
+    types.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    for (event_type, _) in types {
+        rows.push(std::iter::once(event_type.to_string())
+            .chain(users.iter().map(|u| u.type_count(event_type).to_string()))
+            .collect());
+    }
+
+    let headers: Vec<&str> = std::iter::once("").chain(names.iter().copied()).collect();
+    out.push_str(&plain_table(&headers, &rows));
+
+    if users.len() > 1 {
+        if comparison.shared_repos.is_empty() {
+            writeln!(out, "\nNo shared repositories").unwrap();
+        } else {
+            writeln!(out, "\nShared repositories ({})\n", comparison.shared_repos.len()).unwrap();
+            let rows: Vec<Vec<String>> = comparison.shared_repos.iter()
+                .map(|shared| std::iter::once(shared.repo.clone())
+                    .chain(shared.events.iter().map(usize::to_string))
+                    .collect())
+                .collect();
+            let headers: Vec<&str> = std::iter::once("REPOSITORY").chain(names.iter().copied()).collect();
+            out.push_str(&plain_table(&headers, &rows));
+        }
+    }
+
+    out
+}
+
+pub async fn run(args: &CompareArgs) -> Result<()> {
+    let [first, second] = args.users.as_slice() else {
+        anyhow::bail!("compare needs exactly two users, e.g. --user alice --user bob");
+    };
+    if first.eq_ignore_ascii_case(second) {
+        anyhow::bail!("compare needs two different users");
+    }
+
+    let now = Utc::now();
+    let (since, until) = crate::parse_time_range(&args.time, now, args.timezone)?;
+    let until = until.unwrap_or(now);
+
+    let (client, headers) = crate::setup_github_client()?;
+    // Each fetch waits out rate limits on its own, so one user's pages don't hold up the other's
+    let (first_events, second_events) = tokio::join!(
+        // An unknown user fails with `UserNotFound`
+        crate::fetch_events_in_range(&client, &headers, first, since..=until),
+        crate::fetch_events_in_range(&client, &headers, second, since..=until),
+    );
+
+    let mut users = Vec::new();
+    for (username, events) in [(first, first_events), (second, second_events)] {
+        match events {
+            Ok(events) => users.push(UserStats::new(username, &events, args.timezone)),
+            Err(e) => warn!("Error: {}", e),
+        }
+    }
+    if users.is_empty() {
+        anyhow::bail!("Failed to fetch events for both {} and {}", first, second);
+    }
+
+    let comparison = Comparison { shared_repos: shared_repos(&users), users };
+    match args.format {
+        CompareFormat::Table => print!("{}", render_table(&comparison, &args.time)),
+        CompareFormat::Json => println!("{}", serde_json::to_string_pretty(&comparison)?),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,246 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::{Command, ValueEnum};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::timezone::{self, DisplayZone};
+
+const DEFAULT_API_BASE: &str = "https://api.github.com";
+
+static API_BASE: OnceLock<String> = OnceLock::new();
+
+/// Keys the config file accepts, in the order `config get` documents them
+const KEYS: &[&str] = &["user", "time", "format", "timezone", "exclude_repos", "api_base_url", "ca_cert", "insecure", "max_range"];
+
+#[derive(clap::Args)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    command: ConfigCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum ConfigCommand {
+    /// Set a key, e.g. `config set time 7d`; exclude_repos takes a comma-separated list
+    Set { key: String, value: String },
+    /// Print a key's value from the config file
+    Get { key: String },
+    /// Print the path of the config file
+    Path,
+}
+
+/// Defaults from `config.toml`. User, timezone and excluded repositories apply
+/// to every subcommand with those flags; time range and format to `events`,
+/// whose values they match. Flags given on the command line win.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    user: Option<String>,
+    time: Option<String>,
+    format: Option<String>,
+    timezone: Option<String>,
+    #[serde(default)]
+    exclude_repos: Vec<String>,
+    /// REST API root, e.g. "https://github.example.com/api/v3" for GitHub Enterprise
+    api_base_url: Option<String>,
+    /// PEM bundle of extra certificate authorities to trust
+    ca_cert: Option<String>,
+    /// Skip TLS certificate verification
+    insecure: Option<bool>,
+    /// Longest relative range `--time` accepts, e.g. "10y"
+    max_range: Option<String>,
+}
+
+/// `$WIWO_CONFIG`, or `config.toml` in the platform's config directory
+/// (`~/.config/wiwo` on Linux)
+pub fn path() -> Option<PathBuf> {
+    match std::env::var_os("WIWO_CONFIG") {
+        Some(path) => Some(PathBuf::from(path)),
+        None => directories::ProjectDirs::from("", "", "wiwo").map(|dirs| dirs.config_dir().join("config.toml")),
+    }
+}
+
+/// The config file's settings; empty if there isn't one
+pub fn load() -> Result<Config> {
+    let Some(path) = path().filter(|path| path.exists()) else {
+        return Ok(Config::default());
+    };
+    let contents = std::fs::read_to_string(&path)
+        .context(format!("Failed to read config file {}", path.display()))?;
+    // The parse error names the line and the offending key
+    toml_edit::de::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("Invalid config file {}: {}", path.display(), e))
+}
+
+/// Root of the GitHub REST API, without a trailing slash
+pub fn api_base() -> &'static str {
+    API_BASE.get().map_or(DEFAULT_API_BASE, String::as_str)
+}
+
+/// Point API requests at `url`, e.g. "https://github.example.com/api/v3"
+/// for GitHub Enterprise Server, from `--api-url`, `WIWO_API_URL` or the
+/// config file
+pub fn set_api_base(url: &str) -> Result<()> {
+    check_url(url)?;
+    let _ = API_BASE.set(url.trim_end_matches('/').to_string());
+    Ok(())
+}
+
+/// Host of the API, e.g. api.github.com
+pub fn api_host() -> &'static str {
+    let base = api_base();
+    let host = base.split_once("://").map_or(base, |(_, rest)| rest);
+    host.split('/').next().unwrap_or(host)
+}
+
+/// Host serving the web pages for the API: github.com, or for GitHub
+/// Enterprise the host in front of `/api/v3`
+pub fn web_host() -> &'static str {
+    let host = api_host();
+    // api.github.com and GHE.com's api.<subdomain>.ghe.com
+    host.strip_prefix("api.").unwrap_or(host)
+}
+
+/// Root of the web pages, e.g. "https://github.com"
+pub fn web_base() -> String {
+    let scheme = api_base().split_once("://").map_or("https", |(scheme, _)| scheme);
+    format!("{}://{}", scheme, web_host())
+}
+
+fn check_url(url: &str) -> Result<()> {
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        anyhow::bail!("The API URL must start with http:// or https://, e.g. https://github.example.com/api/v3");
+    }
+    Ok(())
+}
+
+/// Make a subcommand's flag default to `values`, if it has that flag
+fn set_default(command: Command, long: &str, values: &[String]) -> Command {
+    let id = command.get_arguments()
+        .find(|arg| arg.get_long() == Some(long))
+        .map(|arg| arg.get_id().clone());
+    match id {
+        Some(id) if !values.is_empty() => command.mut_arg(id, |arg| arg.default_values(values.to_vec())),
+        _ => command,
+    }
+}
+
+impl Config {
+    pub fn max_range(&self) -> Option<&str> {
+        self.max_range.as_deref()
+    }
+
+    /// Install the settings as defaults of the CLI's flags
+    pub fn apply(self, mut command: Command) -> Command {
+        command = set_default(command, "api-url", self.api_base_url.as_slice());
+        command = set_default(command, "ca-cert", self.ca_cert.as_slice());
+        if self.insecure == Some(true) {
+            command = set_default(command, "insecure", &["true".to_string()]);
+        }
+
+        let names: Vec<String> = command.get_subcommands().map(|sub| sub.get_name().to_string()).collect();
+        for name in names {
+            command = command.mut_subcommand(&name, |sub| self.set_defaults(sub, name == "events"));
+        }
+        // Plain `wiwo` takes the flags of `wiwo events`
+        self.set_defaults(command, true)
+    }
+
+    fn set_defaults(&self, mut command: Command, events: bool) -> Command {
+        command = set_default(command, "user", self.user.as_slice());
+        command = set_default(command, "timezone", self.timezone.as_slice());
+        command = set_default(command, "exclude-repo", &self.exclude_repos);
+        if events {
+            command = set_default(command, "time", self.time.as_slice());
+            command = set_default(command, "format", self.format.as_slice());
+        }
+        command
+    }
+}
+
+/// Check a value before it's written, so mistakes surface now rather than on the next run
+fn validate(key: &str, value: &str, max_range: &crate::MaxRange) -> Result<()> {
+    match key {
+        "user" if value.trim().is_empty() => anyhow::bail!("user can't be empty"),
+        "time" => {
+            crate::parse_time_range(value, Utc::now(), DisplayZone::Local)?;
+            max_range.check(value)?;
+        }
+        "format" => {
+            crate::OutputFormat::from_str(value, false)
+                .map_err(|_| anyhow::anyhow!("Invalid format '{}'; see `wiwo events --help` for the choices", value))?;
+        }
+        "timezone" => {
+            timezone::parse_timezone(value).map_err(anyhow::Error::msg)?;
+        }
+        "exclude_repos" => {
+            let globs: Vec<String> = value.split(',').map(|s| s.trim().to_string()).collect();
+            crate::filter::RepoFilter::new(&[], &globs)?;
+        }
+        "api_base_url" => check_url(value)?,
+        "ca_cert" if !std::path::Path::new(value).is_file() => anyhow::bail!("No such file: {}", value),
+        "insecure" if value != "true" && value != "false" => anyhow::bail!("insecure must be true or false"),
+        "max_range" => {
+            crate::parse_duration(value)?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn check_key(key: &str) -> Result<()> {
+    if !KEYS.contains(&key) {
+        anyhow::bail!("Unknown config key '{}'; expected one of {}", key, KEYS.join(", "));
+    }
+    Ok(())
+}
+
+pub fn run(args: &ConfigArgs, max_range: &crate::MaxRange) -> Result<()> {
+    let path = path().context("Could not determine a config directory (no home directory)")?;
+
+    match &args.command {
+        ConfigCommand::Path => println!("{}", path.display()),
+        ConfigCommand::Get { key } => {
+            check_key(key)?;
+            let contents = std::fs::read_to_string(&path).unwrap_or_default();
+            let document: toml_edit::DocumentMut = contents.parse()
+                .map_err(|e| anyhow::anyhow!("Invalid config file {}: {}", path.display(), e))?;
+            let value = document.get(key).and_then(|item| item.as_value());
+            match value {
+                Some(toml_edit::Value::String(s)) => println!("{}", s.value()),
+                Some(toml_edit::Value::Array(array)) => {
+                    let values: Vec<&str> = array.iter().filter_map(|v| v.as_str()).collect();
+                    println!("{}", values.join(","));
+                }
+                Some(other) => println!("{}", other.to_string().trim()),
+                None => anyhow::bail!("{} is not set in {}", key, path.display()),
+            }
+        }
+        ConfigCommand::Set { key, value } => {
+            check_key(key)?;
+            validate(key, value, max_range)?;
+            let contents = match std::fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(e) => return Err(e).context(format!("Failed to read config file {}", path.display())),
+            };
+            // Edit in place so comments and layout survive
+            let mut document: toml_edit::DocumentMut = contents.parse()
+                .map_err(|e| anyhow::anyhow!("Invalid config file {}: {}", path.display(), e))?;
+            document[key.as_str()] = match key.as_str() {
+                "exclude_repos" => toml_edit::value(value.split(',').map(str::trim).filter(|s| !s.is_empty()).collect::<toml_edit::Array>()),
+                "insecure" => toml_edit::value(value == "true"),
+                _ => toml_edit::value(value.as_str()),
+            };
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)
+                    .context(format!("Failed to create {}", parent.display()))?;
+            }
+            crate::output::write_atomic(&path, &document.to_string())?;
+            println!("Set {} in {}", key, path.display());
+        }
+    }
+
+    Ok(())
+}
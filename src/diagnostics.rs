@@ -0,0 +1,84 @@
+use std::sync::{Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit code of `--strict` runs whose results are incomplete
+pub const INCOMPLETE_EXIT_CODE: i32 = 2;
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// What went wrong in ways that leave the results incomplete, in order
+static FAILURES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Silence warnings and progress notes, e.g. for `--quiet`
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Note a failure that leaves the results incomplete, e.g. an endpoint that
+/// couldn't be fetched, for `--strict` to report at the end
+pub fn record_failure(message: String) {
+    FAILURES.lock().unwrap().push(message);
+}
+
+/// Forget recorded failures, for `serve`, which would otherwise keep every
+/// one for as long as it runs
+pub fn clear_failures() {
+    FAILURES.lock().unwrap().clear();
+}
+
+/// With `--strict`, list every recorded failure and exit with
+/// `INCOMPLETE_EXIT_CODE` if there were any. Output has been written by then,
+/// so the partial results are still there to see.
+pub fn exit_if_incomplete() {
+    let failures = FAILURES.lock().unwrap();
+    if failures.is_empty() {
+        return;
+    }
+    use std::io::Write;
+    let _ = std::io::stdout().flush();
+    eprintln!("Error: The results are incomplete after {} {}:", failures.len(),
+        if failures.len() == 1 { "failure" } else { "failures" });
+    for failure in failures.iter() {
+        eprintln!("  {}", failure);
+    }
+    std::process::exit(INCOMPLETE_EXIT_CODE);
+}
+
+/// Whether `WIWO_DEBUG` is set, for notes that only help when tracking
+/// down a problem
+pub fn is_debug() -> bool {
+    static DEBUG: OnceLock<bool> = OnceLock::new();
+    *DEBUG.get_or_init(|| std::env::var_os("WIWO_DEBUG").is_some_and(|v| !v.is_empty()))
+}
+
+/// Print a warning or progress note to stderr unless output is quiet
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        if !$crate::diagnostics::is_quiet() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// Print a note to stderr if `WIWO_DEBUG` is set
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::diagnostics::is_debug() {
+            eprintln!("debug: {}", format_args!($($arg)*));
+        }
+    };
+}
+
+/// Warn about a failure that leaves the results incomplete, and record it for
+/// `--strict`
+macro_rules! failure {
+    ($($arg:tt)*) => {{
+        let message = format!($($arg)*);
+        warn!("Warning: {}", message);
+        $crate::diagnostics::record_failure(message);
+    }};
+}
@@ -0,0 +1,302 @@
+use anyhow::{Context, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use regex::{Regex, RegexBuilder};
+use std::collections::BTreeSet;
+
+use crate::Event;
+use crate::timezone::DisplayZone;
+
+/// Event types the GitHub Events API reports, offered by shell completion for `--type`
+pub const EVENT_TYPES: &[&str] = &[
+    "CommitCommentEvent",
+    "CreateEvent",
+    "DeleteEvent",
+    "ForkEvent",
+    "GollumEvent",
+    "IssueCommentEvent",
+    "IssuesEvent",
+    "MemberEvent",
+    "PublicEvent",
+    "PullRequestEvent",
+    "PullRequestReviewEvent",
+    "PullRequestReviewCommentEvent",
+    "PullRequestReviewThreadEvent",
+    "PushEvent",
+    "ReleaseEvent",
+    "SponsorshipEvent",
+    "WatchEvent",
+];
+
+/// Parser for `--type` values. Any string is accepted, since short forms like
+/// `PR` and types GitHub adds later are valid too, but the known API types are
+/// listed as possible values so shells can complete them.
+#[derive(Clone)]
+pub struct EventTypeParser;
+
+impl clap::builder::TypedValueParser for EventTypeParser {
+    type Value = String;
+
+    fn parse_ref(&self, cmd: &clap::Command, arg: Option<&clap::Arg>, value: &std::ffi::OsStr) -> std::result::Result<String, clap::Error> {
+        clap::builder::StringValueParser::new().parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = clap::builder::PossibleValue> + '_>> {
+        Some(Box::new(EVENT_TYPES.iter().map(clap::builder::PossibleValue::new)))
+    }
+}
+
+/// Whether `value` names the event's type, either as the raw API type
+/// (`PushEvent`) or the short form (`Push`, `PR`), case-insensitively
+fn matches_type(event: &Event, value: &str) -> bool {
+    event.event_type.eq_ignore_ascii_case(value) || event.formatted_type().eq_ignore_ascii_case(value)
+}
+
+/// Point out `--type` and `--exclude-type` values that match none of
+/// `events`, which are usually typos
+pub fn warn_unknown_types(events: &[Event], include: &[String], exclude: &[String]) {
+    let unknown: Vec<&String> = include.iter().chain(exclude)
+        .filter(|value| !events.iter().any(|e| matches_type(e, value)))
+        .collect();
+    if !unknown.is_empty() {
+        let seen: BTreeSet<String> = events.iter().map(|e| e.formatted_type()).collect();
+        let seen: Vec<String> = seen.into_iter().collect();
+        for value in unknown {
+            warn!("Warning: No events of type '{}'. Types seen: {}", value, seen.join(", "));
+        }
+    }
+}
+
+/// Whether an event matches any of `include` (if given) and none of `exclude`
+pub fn type_matches(event: &Event, include: &[String], exclude: &[String]) -> bool {
+    (include.is_empty() || include.iter().any(|t| matches_type(event, t)))
+        && !exclude.iter().any(|t| matches_type(event, t))
+}
+
+/// Repository visibility to keep
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Visibility {
+    Public,
+    Private,
+}
+
+impl Visibility {
+    pub fn matches(self, is_private: bool) -> bool {
+        match self {
+            Visibility::Public => !is_private,
+            Visibility::Private => is_private,
+        }
+    }
+}
+
+/// Whether a repository owner is one of `orgs` (any owner if none are given).
+/// GitHub treats owner names case-insensitively.
+pub fn owner_matches(owner: &str, orgs: &[String]) -> bool {
+    orgs.is_empty() || orgs.iter().any(|org| org.eq_ignore_ascii_case(owner))
+}
+
+/// Whether a repository's primary language is one of `languages` (any if none
+/// are given), case-insensitively; "none" matches repositories without one
+pub fn language_matches(language: Option<&str>, languages: &[String]) -> bool {
+    languages.is_empty() || languages.iter().any(|wanted| match language {
+        Some(language) => wanted.eq_ignore_ascii_case(language),
+        None => wanted.eq_ignore_ascii_case("none"),
+    })
+}
+
+/// Whether an actor login is one of `actors` (any if none are given) and, with
+/// `exclude_bots`, not a bot: GitHub App logins end in "[bot]", and `bots`
+/// names any others.
+pub fn actor_matches(login: &str, actors: &[String], exclude_bots: bool, bots: &[String]) -> bool {
+    let is_bot = login.ends_with("[bot]") || bots.iter().any(|bot| bot.eq_ignore_ascii_case(login));
+    (actors.is_empty() || actors.iter().any(|actor| actor.eq_ignore_ascii_case(login)))
+        && !(exclude_bots && is_bot)
+}
+
+/// Pull request outcome to keep
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PrAction {
+    Opened,
+    Closed,
+    Merged,
+}
+
+impl PrAction {
+    fn matches(self, event: &Event) -> bool {
+        let action = event.payload.action.as_deref();
+        match self {
+            PrAction::Opened => action == Some("opened"),
+            PrAction::Closed => action == Some("closed"),
+            PrAction::Merged => action == Some("closed")
+                && event.payload.pull_request.as_ref().and_then(|pr| pr.merged) == Some(true),
+        }
+    }
+}
+
+/// Whether an event is a pull request event with one of `actions` (any event
+/// if none are given)
+pub fn pr_action_matches(event: &Event, actions: &[PrAction]) -> bool {
+    actions.is_empty()
+        || (event.event_type == "PullRequestEvent" && actions.iter().any(|action| action.matches(event)))
+}
+
+/// `--hours` range of local hours: from the start hour up to, not including,
+/// the end hour. A start after the end wraps past midnight.
+#[derive(Clone, Copy, Debug)]
+pub struct HourRange {
+    start: u32,
+    end: u32,
+}
+
+impl HourRange {
+    pub fn contains(self, hour: u32) -> bool {
+        if self.start < self.end {
+            (self.start..self.end).contains(&hour)
+        } else {
+            hour >= self.start || hour < self.end
+        }
+    }
+}
+
+/// Parse an hour range like `9-18` or `22-6`
+pub fn parse_hour_range(s: &str) -> std::result::Result<HourRange, String> {
+    let invalid = || format!("invalid hour range '{}'; use START-END with hours 0-23 and an end up to 24, e.g. '9-18' or '22-6'", s);
+    let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+    let start: u32 = start.trim().parse().map_err(|_| invalid())?;
+    let end: u32 = end.trim().parse().map_err(|_| invalid())?;
+    if start > 23 || end > 24 || start == end {
+        return Err(invalid());
+    }
+    Ok(HourRange { start, end })
+}
+
+/// Whether an event's local time passes `--weekdays` and `--hours`
+pub fn work_time_matches(event: &Event, timezone: DisplayZone, weekdays: bool, hours: Option<HourRange>) -> bool {
+    use chrono::{Datelike, Timelike};
+    let local = timezone.naive_local(event.created_at);
+    let weekday = local.weekday().num_days_from_monday() < 5;
+    (!weekdays || weekday) && hours.is_none_or(|range| range.contains(local.hour()))
+}
+
+/// Case-insensitive `--search` over an event's text fields
+#[derive(Clone)]
+pub enum Search {
+    Text(String),
+    Regex(Regex),
+}
+
+impl Search {
+    pub fn new(pattern: &str, regex: bool) -> Result<Self> {
+        if regex {
+            let regex = RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .context(format!("Invalid search pattern '{}'", pattern))?;
+            Ok(Search::Regex(regex))
+        } else {
+            Ok(Search::Text(pattern.to_lowercase()))
+        }
+    }
+
+    /// Whether any of the fields contains the text or matches the pattern
+    pub fn matches<'a>(&self, mut fields: impl Iterator<Item = &'a str>) -> bool {
+        match self {
+            Search::Text(text) => fields.any(|field| field.to_lowercase().contains(text)),
+            Search::Regex(regex) => fields.any(|field| regex.is_match(field)),
+        }
+    }
+}
+
+/// Include/exclude globs over full `owner/name` repository names
+#[derive(Clone)]
+pub struct RepoFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+fn build_globs(patterns: &[String]) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        // GitHub owner and repository names are case-insensitive
+        let glob = GlobBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .context(format!("Invalid repository pattern '{}'", pattern))?;
+        builder.add(glob);
+    }
+    Ok(Some(builder.build()?))
+}
+
+impl RepoFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        Ok(Self {
+            include: build_globs(include)?,
+            exclude: build_globs(exclude)?,
+        })
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.include.is_some() || self.exclude.is_some()
+    }
+
+    pub fn matches(&self, full_name: &str) -> bool {
+        self.include.as_ref().is_none_or(|set| set.is_match(full_name))
+            && !self.exclude.as_ref().is_some_and(|set| set.is_match(full_name))
+    }
+}
+
+/// Every event filter at once, for output that can't filter the whole list
+/// after fetching. Expects repository details to be resolved already.
+#[derive(Clone)]
+pub struct EventFilter {
+    pub include_types: Vec<String>,
+    pub exclude_types: Vec<String>,
+    pub repos: RepoFilter,
+    pub orgs: Vec<String>,
+    pub visibility: Option<Visibility>,
+    pub languages: Vec<String>,
+    pub no_forks: bool,
+    pub search: Option<Search>,
+    pub actors: Vec<String>,
+    pub exclude_bots: bool,
+    pub bots: Vec<String>,
+    pub pr_actions: Vec<PrAction>,
+    pub weekdays: bool,
+    pub hours: Option<HourRange>,
+    pub timezone: DisplayZone,
+}
+
+impl EventFilter {
+    /// Whether matching needs repository details looked up
+    pub fn needs_details(&self) -> bool {
+        self.visibility.is_some() || !self.languages.is_empty() || self.no_forks
+    }
+
+    /// The filters that don't need repository details, to narrow down what
+    /// needs looking up
+    pub fn without_details(&self) -> EventFilter {
+        EventFilter { visibility: None, languages: Vec::new(), no_forks: false, ..self.clone() }
+    }
+
+    /// The filters other than `--weekdays` and `--hours`, to tell what those removed
+    pub fn without_work_time(&self) -> EventFilter {
+        EventFilter { weekdays: false, hours: None, ..self.clone() }
+    }
+
+    pub fn matches(&self, event: &Event) -> bool {
+        type_matches(event, &self.include_types, &self.exclude_types)
+            && self.repos.matches(&event.repo.name)
+            && owner_matches(event.repo.owner(), &self.orgs)
+            // Unknown visibility is treated as private, as when filtering the full list
+            && self.visibility.is_none_or(|v| v.matches(event.repo.private.unwrap_or(true)))
+            && (self.languages.is_empty()
+                || (event.repo.private.is_some() && language_matches(event.repo.language.as_deref(), &self.languages)))
+            && !(self.no_forks && event.repo.fork)
+            && self.search.as_ref().is_none_or(|search| search.matches(event.search_text()))
+            && actor_matches(event.actor_login(), &self.actors, self.exclude_bots, &self.bots)
+            && pr_action_matches(event, &self.pr_actions)
+            && work_time_matches(event, self.timezone, self.weekdays, self.hours)
+    }
+}
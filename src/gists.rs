@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+use crate::net::SendRetrying;
+use crate::{Actor, Event, Payload, Repository};
+
+/// Event type of rows made from gists, which the Events API doesn't report
+pub const GIST_EVENT: &str = "Gist";
+
+#[derive(Debug, Deserialize)]
+struct Gist {
+    id: String,
+    html_url: String,
+    /// `false` for secret gists
+    public: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    /// Keyed by file name, in alphabetical order
+    files: BTreeMap<String, serde_json::Value>,
+    owner: Option<Actor>,
+}
+
+impl Gist {
+    fn event(&self, username: &str, created_at: DateTime<Utc>, action: &str) -> Event {
+        // Gists aren't repositories, but the name and URL say which one it was
+        let file = self.files.keys().next().unwrap_or(&self.id);
+        Event {
+            id: None,
+            event_type: GIST_EVENT.to_string(),
+            created_at,
+            actor: self.owner.clone(),
+            user: Some(username.to_string()),
+            repo: Repository {
+                id: None,
+                name: format!("gist:{}", file),
+                html_url: self.html_url.clone(),
+                private: Some(!self.public),
+                clone_url: String::new(),
+                fork: false,
+                language: None,
+                size: 0,
+            },
+            payload: Payload { action: Some(action.to_string()), ..Payload::default() },
+        }
+    }
+}
+
+/// One event per gist created in the range and one per gist updated in it
+/// since. Secret gists are included when the token belongs to the user.
+pub async fn fetch_gist_events(client: &reqwest::Client, headers: &HeaderMap, username: &str, since: DateTime<Utc>) -> Result<Vec<Event>> {
+    let is_self = matches!(crate::get_authenticated_user(client, headers).await,
+        Ok(Some(login)) if login.eq_ignore_ascii_case(username));
+    let base_url = if is_self {
+        format!("{}/gists", crate::config::api_base())
+    } else {
+        format!("{}/users/{}/gists", crate::config::api_base(), username)
+    };
+
+    let mut events = Vec::new();
+    let mut page = 1;
+    loop {
+        // `since` selects gists updated after it, which covers those created after it too
+        let response = client
+            .get(&base_url)
+            .headers(headers.clone())
+            .query(&[("since", since.to_rfc3339()), ("per_page", "100".to_string()), ("page", page.to_string())])
+            .send_retrying()
+            .await
+            .context(format!("Failed to fetch gists for {}", username))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to fetch gists for {} ({})", username, response.status());
+        }
+        let gists: Vec<Gist> = response.json().await
+            .context("Failed to parse gists response")?;
+        if gists.is_empty() {
+            break;
+        }
+
+        for gist in &gists {
+            if gist.created_at >= since {
+                events.push(gist.event(username, gist.created_at, "created"));
+            }
+            if gist.updated_at >= since && gist.updated_at != gist.created_at {
+                events.push(gist.event(username, gist.updated_at, "updated"));
+            }
+        }
+        page += 1;
+    }
+
+    Ok(events)
+}
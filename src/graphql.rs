@@ -0,0 +1,201 @@
+use anyhow::{Context, Result};
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::RepositoryDetails;
+use crate::net::SendRetrying;
+
+/// Repositories looked up per GraphQL request, the most GitHub allows for
+/// the nodes of one query
+pub const BATCH_SIZE: usize = 100;
+
+/// Fields fetched for every repository, matching what `GET /repos/{name}`
+/// gives the REST path
+const REPOSITORY_FIELDS: &str = "isPrivate isFork isArchived primaryLanguage { name }";
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    data: Option<HashMap<String, Option<ApiRepository>>>,
+    #[serde(default)]
+    errors: Vec<ApiError>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiRepository {
+    is_private: bool,
+    is_fork: bool,
+    is_archived: bool,
+    primary_language: Option<Language>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Language {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    #[serde(rename = "type")]
+    error_type: Option<String>,
+    message: String,
+}
+
+/// What GitHub said about one repository of a batch
+#[derive(Debug)]
+pub enum Lookup {
+    Found(RepositoryDetails),
+    /// Deleted, renamed away, or private and out of the token's reach
+    NotFound,
+}
+
+/// The endpoint: `/graphql` on github.com, `/api/graphql` on GitHub
+/// Enterprise Server, whose REST API lives under `/api/v3`
+fn endpoint() -> String {
+    let base = crate::config::api_base();
+    match base.strip_suffix("/v3") {
+        Some(api) if api.ends_with("/api") => format!("{}/graphql", api),
+        _ => format!("{}/graphql", base),
+    }
+}
+
+/// A query fetching each of `names` (`owner/name`) under the alias `r<index>`,
+/// and its variables. Names are passed as variables so nothing in them needs
+/// escaping.
+fn query(names: &[&str]) -> (String, serde_json::Value) {
+    let mut parameters = Vec::new();
+    let mut fields = Vec::new();
+    let mut variables = serde_json::Map::new();
+    for (i, name) in names.iter().enumerate() {
+        let (owner, repo) = name.split_once('/').unwrap_or((name, ""));
+        parameters.push(format!("$o{i}: String!, $n{i}: String!"));
+        fields.push(format!("r{i}: repository(owner: $o{i}, name: $n{i}) {{ {REPOSITORY_FIELDS} }}"));
+        variables.insert(format!("o{i}"), owner.into());
+        variables.insert(format!("n{i}"), repo.into());
+    }
+    let query = format!("query({}) {{ {} }}", parameters.join(", "), fields.join(" "));
+    (query, serde_json::Value::Object(variables))
+}
+
+/// Match a response body to the `names` queried. Repositories GitHub couldn't
+/// find come back as `null` with a NOT_FOUND error; any other error means the
+/// batch as a whole can't be trusted.
+fn parse(names: &[&str], body: &str) -> Result<HashMap<String, Lookup>> {
+    let response: Response = serde_json::from_str(body).context("Failed to parse the GraphQL response")?;
+    if let Some(error) = response.errors.iter().find(|e| e.error_type.as_deref() != Some("NOT_FOUND")) {
+        anyhow::bail!("GraphQL error: {}", error.message);
+    }
+    let Some(mut data) = response.data else {
+        anyhow::bail!("The GraphQL response has no data");
+    };
+
+    let mut found = HashMap::new();
+    for (i, name) in names.iter().enumerate() {
+        let lookup = match data.remove(&format!("r{i}")).flatten() {
+            Some(repository) => Lookup::Found(RepositoryDetails {
+                private: repository.is_private,
+                fork: repository.is_fork,
+                archived: repository.is_archived,
+                language: repository.primary_language.map(|l| l.name),
+                not_found: false,
+            }),
+            None => Lookup::NotFound,
+        };
+        found.insert(name.to_string(), lookup);
+    }
+    Ok(found)
+}
+
+/// Look up to `BATCH_SIZE` repositories in one request. Needs a token:
+/// GitHub's GraphQL API doesn't take anonymous requests.
+pub async fn repositories(client: &reqwest::Client, headers: &HeaderMap, names: &[&str]) -> Result<HashMap<String, Lookup>> {
+    let (query, variables) = query(names);
+    let response = client
+        .post(endpoint())
+        .headers(headers.clone())
+        .json(&serde_json::json!({ "query": query, "variables": variables }))
+        .send_retrying()
+        .await
+        .context("Failed to send the GraphQL request")?;
+    crate::ratelimit::record(response.headers());
+    crate::auth::record_scopes(response.headers());
+    let status = response.status();
+    let body = response.text().await.context("Failed to read the GraphQL response")?;
+    if !status.is_success() {
+        anyhow::bail!("GraphQL request failed ({})", status);
+    }
+    parse(names, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(lookup: Option<&Lookup>) -> &RepositoryDetails {
+        match lookup {
+            Some(Lookup::Found(details)) => details,
+            other => panic!("expected details, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_found_and_missing_repositories() {
+        let body = r#"{
+            "data": {
+                "r0": { "isPrivate": true, "isFork": false, "isArchived": false, "primaryLanguage": { "name": "Rust" } },
+                "r1": null,
+                "r2": { "isPrivate": false, "isFork": true, "isArchived": true, "primaryLanguage": null }
+            },
+            "errors": [
+                { "type": "NOT_FOUND", "path": ["r1"], "message": "Could not resolve to a Repository with the name 'octocat/gone'." }
+            ]
+        }"#;
+        let names = ["octocat/secret", "octocat/gone", "octocat/fork"];
+        let lookups = parse(&names, body).unwrap();
+
+        let secret = found(lookups.get("octocat/secret"));
+        assert!(secret.private && !secret.fork && !secret.archived);
+        assert_eq!(secret.language.as_deref(), Some("Rust"));
+        assert!(matches!(lookups.get("octocat/gone"), Some(Lookup::NotFound)));
+        let fork = found(lookups.get("octocat/fork"));
+        assert!(!fork.private && fork.fork && fork.archived);
+        assert_eq!(fork.language, None);
+    }
+
+    #[test]
+    fn aliases_missing_from_the_data_are_not_found() {
+        let lookups = parse(&["octocat/hello"], r#"{ "data": {} }"#).unwrap();
+        assert!(matches!(lookups.get("octocat/hello"), Some(Lookup::NotFound)));
+    }
+
+    #[test]
+    fn other_errors_fail_the_batch() {
+        let body = r#"{
+            "data": { "r0": null },
+            "errors": [{ "type": "RATE_LIMITED", "message": "API rate limit exceeded" }]
+        }"#;
+        let error = parse(&["octocat/hello"], body).unwrap_err();
+        assert!(error.to_string().contains("API rate limit exceeded"), "{}", error);
+
+        // Errors without a type, like a malformed query, too
+        let body = r#"{ "errors": [{ "message": "Parse error on \"}\"" }] }"#;
+        assert!(parse(&["octocat/hello"], body).is_err());
+    }
+
+    #[test]
+    fn responses_without_data_or_json_fail() {
+        assert!(parse(&["octocat/hello"], r#"{ "errors": [] }"#).is_err());
+        assert!(parse(&["octocat/hello"], "<html>Bad gateway</html>").is_err());
+    }
+
+    #[test]
+    fn query_passes_names_as_variables() {
+        let (query, variables) = query(&["octocat/hello", "my-org/weird\"name"]);
+        assert_eq!(
+            query,
+            format!("query($o0: String!, $n0: String!, $o1: String!, $n1: String!) {{ r0: repository(owner: $o0, name: $n0) {{ {0} }} r1: repository(owner: $o1, name: $n1) {{ {0} }} }}", REPOSITORY_FIELDS)
+        );
+        assert_eq!(variables, serde_json::json!({ "o0": "octocat", "n0": "hello", "o1": "my-org", "n1": "weird\"name" }));
+    }
+}
@@ -0,0 +1,196 @@
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::output::RenderContext;
+use crate::Event;
+
+/// GitHub shows a year of activity; longer ranges are capped to this many weeks
+const MAX_WEEKS: usize = 53;
+
+/// Shades from no activity to the busiest days
+const SHADES: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+/// 256-color greens matching the shades above
+const COLORS: [u8; 5] = [240, 22, 28, 34, 46];
+
+const WEEKDAYS: [&str; 7] = ["Mon", "   ", "Wed", "   ", "Fri", "   ", "Sun"];
+
+/// Map a day's count onto a shade index, relative to the busiest day
+fn level(count: usize, max: usize) -> usize {
+    if count == 0 || max == 0 {
+        0
+    } else {
+        (count * 4).div_ceil(max).clamp(1, 4)
+    }
+}
+
+fn cell(level: usize, color: bool) -> String {
+    if color {
+        format!("\x1b[38;5;{}m{}\x1b[0m", COLORS[level], SHADES[level])
+    } else {
+        SHADES[level].to_string()
+    }
+}
+
+/// Count events per calendar day in the display time zone
+fn daily_counts(events: &[Event], context: &RenderContext) -> HashMap<NaiveDate, usize> {
+    let mut counts: HashMap<NaiveDate, usize> = HashMap::new();
+    for event in events {
+        *counts.entry(context.timezone.naive_local(event.created_at).date()).or_default() += 1;
+    }
+    counts
+}
+
+/// The last day of the requested range in the display time zone: today,
+/// unless the range ended earlier
+fn last_day(context: &RenderContext) -> NaiveDate {
+    context.timezone.naive_local(context.until.unwrap_or_else(Utc::now)).date()
+}
+
+/// Eight-level bars used by the sparkline
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One-line chart of events per day across the requested range, with the
+/// first and last date labelled underneath
+pub fn sparkline(events: &[Event], context: &RenderContext) -> String {
+    let counts = daily_counts(events, context);
+    let start = context.timezone.naive_local(context.since).date();
+    let end = last_day(context);
+    let days = ((end - start).num_days() + 1).max(1) as usize;
+
+    // Aggregate several days per character when the range is wider than the terminal
+    let width = context.max_width.unwrap_or(days).max(1);
+    let days_per_bar = days.div_ceil(width);
+    let buckets: Vec<usize> = (0..days.div_ceil(days_per_bar))
+        .map(|bucket| {
+            (0..days_per_bar)
+                .map(|offset| start + Duration::days((bucket * days_per_bar + offset) as i64))
+                .filter(|date| *date <= end)
+                .map(|date| counts.get(&date).copied().unwrap_or(0))
+                .sum()
+        })
+        .collect();
+
+    let max = buckets.iter().copied().max().unwrap_or(0).max(1);
+    let line: String = buckets.iter()
+        .map(|&count| BARS[(count * (BARS.len() - 1)).div_ceil(max).min(BARS.len() - 1)])
+        .collect();
+
+    let first = start.format("%Y-%m-%d").to_string();
+    let last = end.format("%Y-%m-%d").to_string();
+    let gap = buckets.len().saturating_sub(first.len() + last.len()).max(1);
+
+    format!("{}\n{}{}{}\n\n", line, first, " ".repeat(gap), last)
+}
+
+/// Render a contribution grid of weeks (columns) by weekdays (rows)
+pub fn render(events: &[Event], context: &RenderContext) -> String {
+    let counts = daily_counts(events, context);
+
+    let end = last_day(context);
+    let start = context.timezone.naive_local(context.since).date();
+    let monday = |d: NaiveDate| d - Duration::days(d.weekday().num_days_from_monday() as i64);
+
+    let total_weeks = ((monday(end) - monday(start)).num_days() / 7 + 1) as usize;
+    // Each week takes two columns, after the weekday labels
+    let fits = context.max_width.map_or(MAX_WEEKS, |w| w.saturating_sub(4) / 2).min(MAX_WEEKS);
+    let weeks = total_weeks.min(fits.max(1));
+    let first_monday = monday(end) - Duration::weeks(weeks as i64 - 1);
+
+    let max = counts.values().copied().max().unwrap_or(0);
+    let mut out = String::new();
+
+    // Month labels above the first week of each month
+    let mut months = String::from("    ");
+    let mut last_month = None;
+    for week in 0..weeks {
+        let date = first_monday + Duration::weeks(week as i64);
+        let position = 4 + 2 * week;
+        if last_month != Some(date.month()) && months.len() <= position {
+            months.push_str(&" ".repeat(position - months.len()));
+            months.push_str(&date.format("%b").to_string());
+        }
+        last_month = Some(date.month());
+    }
+    writeln!(out, "{}", months.trim_end()).unwrap();
+
+    for (weekday, label) in WEEKDAYS.iter().enumerate() {
+        let mut row = format!("{} ", label);
+        for week in 0..weeks {
+            let date = first_monday + Duration::weeks(week as i64) + Duration::days(weekday as i64);
+            if date < start || date > end {
+                row.push_str("  ");
+                continue;
+            }
+            let count = counts.get(&date).copied().unwrap_or(0);
+            row.push_str(&cell(level(count, max), context.color));
+            row.push(' ');
+        }
+        writeln!(out, "{}", row.trim_end()).unwrap();
+    }
+
+    let legend: Vec<String> = (0..SHADES.len()).map(|l| cell(l, context.color)).collect();
+    writeln!(out, "\n    Less {} More  ({} events, busiest day {})", legend.join(" "), events.len(), max).unwrap();
+    if weeks < total_weeks {
+        writeln!(out, "    Note: showing the last {} of {} weeks", weeks, total_weeks).unwrap();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{Column, TableStyle, TimeFormat};
+    use crate::timezone::DisplayZone;
+
+    /// February 2025, as `--time last-month` gives it
+    fn context() -> RenderContext<'static> {
+        RenderContext {
+            username: "octocat",
+            time_range: "last-month",
+            columns: Column::DEFAULT,
+            time_format: TimeFormat::Absolute,
+            timezone: DisplayZone::Named(chrono_tz::UTC),
+            color: false,
+            max_width: None,
+            hyperlinks: false,
+            group_by: None,
+            summary: None,
+            since: "2025-02-01T00:00:00Z".parse().unwrap(),
+            until: Some("2025-02-28T23:59:59Z".parse().unwrap()),
+            sparkline: true,
+            table_style: TableStyle::Ascii,
+            filtered: false,
+        }
+    }
+
+    fn events() -> Vec<Event> {
+        serde_json::from_value(serde_json::json!([
+            { "type": "PushEvent", "created_at": "2025-02-03T09:00:00Z", "repo": { "name": "a/x" } },
+            { "type": "PushEvent", "created_at": "2025-02-03T10:00:00Z", "repo": { "name": "a/x" } },
+            { "type": "PushEvent", "created_at": "2025-02-28T10:00:00Z", "repo": { "name": "a/x" } }
+        ])).unwrap()
+    }
+
+    #[test]
+    fn sparkline_ends_with_the_range() {
+        let sparkline = sparkline(&events(), &context());
+        let lines: Vec<&str> = sparkline.lines().collect();
+        assert_eq!(lines[0].chars().count(), 28);
+        assert!(lines[0].ends_with('▅'), "{}", lines[0]);
+        assert!(lines[1].starts_with("2025-02-01") && lines[1].ends_with("2025-02-28"), "{}", lines[1]);
+    }
+
+    #[test]
+    fn heatmap_ends_with_the_range() {
+        let heatmap = render(&events(), &context());
+        let lines: Vec<&str> = heatmap.lines().collect();
+        // The weeks from the one of Saturday the 1st to the one of Friday the 28th
+        assert_eq!(lines[0], "    Jan");
+        assert_eq!(lines[1], "Mon   █ · · ·");
+        assert_eq!(lines[5], "Fri   · · · ▒");
+        assert_eq!(lines[6], "    · · · ·");
+        assert!(!heatmap.contains("Note:"), "{}", heatmap);
+    }
+}
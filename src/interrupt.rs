@@ -0,0 +1,64 @@
+use std::sync::OnceLock;
+use tokio_util::sync::CancellationToken;
+
+/// Exit code after Ctrl+C, as a shell reports a process killed by SIGINT
+pub const EXIT_CODE: i32 = 130;
+
+static TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+
+tokio::task_local! {
+    /// Stands in for Ctrl+C's token within `scope`
+    static SCOPED: CancellationToken;
+}
+
+/// Cancelled by the first Ctrl+C once `install` has run, or within `scope`
+/// by whoever holds that token; long-running work checks it to stop early,
+/// keeping what it has
+pub fn token() -> CancellationToken {
+    SCOPED.try_with(CancellationToken::clone)
+        .unwrap_or_else(|_| TOKEN.get_or_init(CancellationToken::new).clone())
+}
+
+/// Run `future` interrupted by cancelling `token` rather than by Ctrl+C, so
+/// tests can interrupt one fetch among others. Tasks it spawns don't inherit
+/// the token.
+#[cfg(test)]
+pub async fn scope<F: Future>(token: CancellationToken, future: F) -> F::Output {
+    SCOPED.scope(token, future).await
+}
+
+pub fn is_interrupted() -> bool {
+    token().is_cancelled()
+}
+
+/// Turn Ctrl+C into cancellation, so that fetching stops and whatever was
+/// collected still gets shown. A second Ctrl+C quits at once.
+pub fn install() {
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        warn!("Interrupted; showing the events collected so far (press Ctrl+C again to quit at once)");
+        token().cancel();
+        if tokio::signal::ctrl_c().await.is_ok() {
+            std::process::exit(EXIT_CODE);
+        }
+    });
+}
+
+/// `future`'s output, or `None` if interrupted before it's done
+pub async fn unless_interrupted<F: Future>(future: F) -> Option<F::Output> {
+    tokio::select! {
+        biased;
+        _ = token().cancelled_owned() => None,
+        output = future => Some(output),
+    }
+}
+
+/// Say that the results just shown are partial, and exit as interrupted
+pub fn exit_with_partial_results() -> ! {
+    use std::io::Write;
+    let _ = std::io::stdout().flush();
+    warn!("(interrupted — partial results)");
+    std::process::exit(EXIT_CODE);
+}
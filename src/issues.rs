@@ -0,0 +1,153 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::filter::{self, RepoFilter};
+use crate::output::plain_table;
+use crate::search::{self, SearchItem};
+use crate::timezone::{self, DisplayZone};
+
+#[derive(clap::Args)]
+pub struct IssuesArgs {
+    /// GitHub username (defaults to authenticated user if GH_TOKEN is set)
+    #[arg(short, long)]
+    user: Option<String>,
+    /// Time range to cover (e.g., "30d", "1y", "last-month"): issues opened in it,
+    /// or commented on and updated since
+    #[arg(short, long, default_value = "30d", value_parser = crate::parse_time_arg)]
+    time: String,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = IssuesFormat::Table)]
+    format: IssuesFormat,
+    /// Only show repositories owned by this organization or user (repeatable)
+    #[arg(long = "org")]
+    orgs: Vec<String>,
+    /// Only show repositories matching this glob over "owner/name", e.g. "my-org/*" (repeatable)
+    #[arg(long = "repo")]
+    repos: Vec<String>,
+    /// Hide repositories matching this glob, e.g. "*-infra" (repeatable)
+    #[arg(long = "exclude-repo")]
+    exclude_repos: Vec<String>,
+    /// Time zone for displaying dates: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum IssuesFormat {
+    Table,
+    Json,
+}
+
+/// How the user took part in an issue
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Role {
+    Author,
+    Commenter,
+    Both,
+}
+
+impl Role {
+    fn label(self) -> &'static str {
+        match self {
+            Role::Author => "author",
+            Role::Commenter => "commenter",
+            Role::Both => "both",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct IssueSummary {
+    repo: String,
+    number: u64,
+    title: String,
+    state: String,
+    html_url: String,
+    role: Role,
+    last_activity: DateTime<Utc>,
+}
+
+impl IssueSummary {
+    fn new(item: SearchItem, role: Role) -> Self {
+        IssueSummary {
+            repo: item.repo_name().to_string(),
+            number: item.number,
+            title: item.title,
+            state: item.state,
+            html_url: item.html_url,
+            role,
+            last_activity: item.updated_at,
+        }
+    }
+}
+
+/// Issues from both searches, one per issue with the roles combined, most
+/// recently active first
+fn merge(authored: Vec<SearchItem>, commented: Vec<SearchItem>) -> Vec<IssueSummary> {
+    let mut issues: HashMap<String, IssueSummary> = HashMap::new();
+    for (items, role) in [(authored, Role::Author), (commented, Role::Commenter)] {
+        for item in items {
+            match issues.get_mut(&item.html_url) {
+                Some(issue) if issue.role != role => issue.role = Role::Both,
+                Some(_) => {}
+                None => {
+                    issues.insert(item.html_url.clone(), IssueSummary::new(item, role));
+                }
+            }
+        }
+    }
+
+    let mut issues: Vec<IssueSummary> = issues.into_values().collect();
+    issues.sort_by(|a, b| b.last_activity.cmp(&a.last_activity).then_with(|| a.html_url.cmp(&b.html_url)));
+    issues
+}
+
+fn render_table(issues: &[IssueSummary], timezone: DisplayZone) -> String {
+    if issues.is_empty() {
+        return "No issues found.\n".to_string();
+    }
+
+    let rows: Vec<Vec<String>> = issues.iter()
+        .map(|issue| vec![
+            issue.repo.clone(),
+            format!("#{}", issue.number),
+            issue.state.clone(),
+            issue.role.label().to_string(),
+            timezone.format(issue.last_activity, "%Y-%m-%d"),
+            issue.title.clone(),
+        ])
+        .collect();
+    plain_table(&["REPOSITORY", "NUMBER", "STATE", "ROLE", "LAST ACTIVITY", "TITLE"], &rows)
+}
+
+pub async fn run(args: &IssuesArgs) -> Result<()> {
+    let repo_filter = RepoFilter::new(&args.repos, &args.exclude_repos)?;
+    let now = Utc::now();
+    let (since, until) = crate::parse_time_range(&args.time, now, args.timezone)?;
+
+    let (client, headers) = crate::setup_github_client()?;
+    let username = crate::resolve_username(&client, &headers, args.user.as_deref()).await?;
+
+    // Search can't filter on when a comment was made, so commented-on issues
+    // are those updated in the range
+    let authored = format!("type:issue author:{} {}", username, search::range_qualifier("created", since, until));
+    let commented = format!("type:issue commenter:{} {}", username, search::range_qualifier("updated", since, until));
+    let authored = search::search_issues(&client, &headers, &authored).await?;
+    let commented = search::search_issues(&client, &headers, &commented).await?;
+
+    let mut issues = merge(authored, commented);
+    issues.retain(|issue| {
+        let owner = issue.repo.split_once('/').map_or("", |(owner, _)| owner);
+        repo_filter.matches(&issue.repo) && filter::owner_matches(owner, &args.orgs)
+    });
+
+    match args.format {
+        IssuesFormat::Table => print!("{}", render_table(&issues, args.timezone)),
+        IssuesFormat::Json => println!("{}", serde_json::to_string_pretty(&issues)?),
+    }
+
+    Ok(())
+}
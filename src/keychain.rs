@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+use std::sync::OnceLock;
+
+/// Keychain entry holding the token
+const SERVICE: &str = "wiwo";
+const ACCOUNT: &str = "github-token";
+
+/// The keychain's token, read once at startup by `load`
+static TOKEN: OnceLock<Option<String>> = OnceLock::new();
+
+/// Turn a backend failure into something the user can act on
+fn explain(e: keyring::Error) -> anyhow::Error {
+    match e {
+        keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_) => anyhow::anyhow!(
+            "The OS keychain isn't available ({}). On Linux it needs a Secret Service provider such as \
+             GNOME Keyring or KeePassXC running; otherwise set GH_TOKEN or use `wiwo auth login`", e),
+        e => anyhow::anyhow!("Keychain error: {}", e),
+    }
+}
+
+/// Run a keychain call off the async runtime, turning a panicking backend into an error
+async fn with_entry<T: Send + 'static>(f: impl FnOnce(keyring::Entry) -> keyring::Result<T> + Send + 'static) -> Result<keyring::Result<T>> {
+    tokio::task::spawn_blocking(move || keyring::Entry::new(SERVICE, ACCOUNT).and_then(f))
+        .await
+        .map_err(|_| anyhow::anyhow!("The OS keychain backend crashed; set GH_TOKEN or use `wiwo auth login` instead"))
+}
+
+/// Read the token for `token()`. A missing entry or keychain leaves it unset:
+/// the other sources still apply.
+pub async fn load() {
+    if TOKEN.get().is_some() {
+        return;
+    }
+    let token = with_entry(|entry| entry.get_password()).await.ok()
+        .and_then(Result::ok)
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty());
+    let _ = TOKEN.set(token);
+}
+
+pub fn stored_token() -> Option<String> {
+    TOKEN.get().cloned().flatten()
+}
+
+/// Prompt for a token without echoing it, check it with GitHub and store it
+pub async fn set_token() -> Result<()> {
+    let token = rpassword::prompt_password("GitHub token: ")
+        .context("Failed to read the token")?;
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        anyhow::bail!("No token entered");
+    }
+
+    let (client, mut headers) = crate::setup_github_client()?;
+    headers.insert(reqwest::header::AUTHORIZATION, format!("Bearer {}", token).parse()
+        .context("Invalid GitHub token format")?);
+    let Some(login) = crate::verify_authenticated_user(&client, &headers).await? else {
+        anyhow::bail!("GitHub didn't accept the token");
+    };
+
+    with_entry(move |entry| entry.set_password(&token)).await?.map_err(explain)?;
+    println!("Stored the token for {} in the OS keychain", login);
+    Ok(())
+}
+
+pub async fn delete_token() -> Result<()> {
+    match with_entry(|entry| entry.delete_credential()).await? {
+        Ok(()) => println!("Removed the token from the OS keychain"),
+        Err(keyring::Error::NoEntry) => println!("No token in the OS keychain to remove"),
+        Err(e) => return Err(explain(e)),
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// The netrc token and the file it came from, looked up at most once per run
+static TOKEN: OnceLock<Option<(String, PathBuf)>> = OnceLock::new();
+
+/// One `machine` block; `default` blocks are left out, since their
+/// credentials are meant for some other host
+#[derive(Debug)]
+struct Machine {
+    name: String,
+    password: Option<String>,
+}
+
+/// `~/.netrc`, or `~/_netrc` as it's usually called on Windows
+fn path() -> Option<PathBuf> {
+    let home = directories::BaseDirs::new()?.home_dir().to_path_buf();
+    [".netrc", "_netrc"].into_iter()
+        .map(|name| home.join(name))
+        .find(|path| path.is_file())
+}
+
+/// The `machine` blocks of a netrc file, e.g.
+///
+/// ```text
+/// machine api.github.com
+///   login octocat
+///   password ghp_...
+/// ```
+///
+/// Macro definitions are skipped up to the blank line ending them, as are
+/// `#` comments. Anything else unexpected fails the whole file, since a
+/// misread could send some other host's password to GitHub.
+fn parse(contents: &str) -> Result<Vec<Machine>, String> {
+    let mut machines: Vec<Machine> = Vec::new();
+    // Whether the current block is `default`, whose values are ignored
+    let mut in_default = false;
+    let mut in_macro = false;
+    for line in contents.lines() {
+        if in_macro {
+            in_macro = !line.trim().is_empty();
+            continue;
+        }
+        let line = line.split_once('#').map_or(line, |(before, _)| before);
+        let mut tokens = line.split_whitespace();
+        while let Some(keyword) = tokens.next() {
+            let mut value = || tokens.next().ok_or_else(|| format!("'{}' without a value", keyword));
+            match keyword {
+                "machine" => {
+                    machines.push(Machine { name: value()?.to_string(), password: None });
+                    in_default = false;
+                }
+                "default" => in_default = true,
+                "login" | "account" | "password" => {
+                    let value = value()?;
+                    if in_default {
+                        continue;
+                    }
+                    let Some(machine) = machines.last_mut() else {
+                        return Err(format!("'{}' before any machine", keyword));
+                    };
+                    if keyword == "password" {
+                        machine.password = Some(value.to_string());
+                    }
+                }
+                "macdef" => {
+                    value()?;
+                    in_macro = true;
+                    break;
+                }
+                other => return Err(format!("unknown keyword '{}'", other)),
+            }
+        }
+    }
+    Ok(machines)
+}
+
+/// Password of the API host's entry, e.g. `machine api.github.com`, or
+/// failing that the web host's, e.g. `machine github.com`
+fn lookup(machines: &[Machine]) -> Option<String> {
+    // netrc names hosts without a port
+    let strip_port = |host: &'static str| host.split(':').next().unwrap_or(host);
+    [strip_port(crate::config::api_host()), strip_port(crate::config::web_host())].into_iter()
+        .find_map(|host| machines.iter().find(|m| m.name.eq_ignore_ascii_case(host)))
+        .and_then(|machine| machine.password.clone())
+        .filter(|password| !password.is_empty())
+}
+
+fn read_token() -> Option<(String, PathBuf)> {
+    let path = path()?;
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            debug!("Skipping {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    match parse(&contents) {
+        Ok(machines) => lookup(&machines).map(|token| (token, path)),
+        Err(e) => {
+            debug!("Skipping {}, which isn't a valid netrc file: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// The token in `~/.netrc` for the API host, and the file's path
+pub fn token() -> Option<(String, PathBuf)> {
+    TOKEN.get_or_init(read_token).clone()
+}
@@ -0,0 +1,275 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, AUTHORIZATION};
+use serde::{Deserialize, Serialize};
+
+use crate::net::{PageLinks, SendRetrying};
+use crate::output::plain_table;
+use crate::timezone::{self, DisplayZone};
+
+#[derive(clap::Args)]
+pub struct NotificationsArgs {
+    /// Include notifications already marked as read
+    #[arg(long)]
+    all: bool,
+    /// Only notifications for threads you're directly part of: mentioned, assigned, authored or commented
+    #[arg(long)]
+    participating: bool,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = NotificationsFormat::Table)]
+    format: NotificationsFormat,
+    /// Time zone for displaying dates: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum NotificationsFormat {
+    Table,
+    Json,
+}
+
+/// One thread from `GET /notifications`
+#[derive(Debug, Deserialize)]
+struct ApiNotification {
+    reason: String,
+    unread: bool,
+    updated_at: DateTime<Utc>,
+    subject: Subject,
+    repository: NotificationRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct Subject {
+    title: String,
+    /// API URL of the issue, pull request, commit or release; absent for
+    /// discussions and check suites
+    url: Option<String>,
+    #[serde(rename = "type")]
+    subject_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotificationRepository {
+    full_name: String,
+    html_url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Notification {
+    reason: String,
+    repo: String,
+    #[serde(rename = "type")]
+    subject_type: String,
+    title: String,
+    html_url: String,
+    unread: bool,
+    updated_at: DateTime<Utc>,
+}
+
+/// The web page for a notification's subject. The API only gives its API URL,
+/// e.g. `.../repos/o/r/pulls/12`, whose path mostly but not entirely matches
+/// the web one (`/pull/12`); releases are linked by id, which the web doesn't
+/// know, and some subjects have no URL at all, so those fall back to the
+/// matching page of the repository.
+fn subject_html_url(subject: &Subject, repo: &NotificationRepository) -> String {
+    let prefix = format!("/repos/{}/", repo.full_name);
+    let path = subject.url.as_deref()
+        .and_then(|url| url.find(&prefix).map(|i| &url[i + prefix.len()..]));
+    let segments: Vec<&str> = path.map(|p| p.split('/').collect()).unwrap_or_default();
+
+    let page = match (segments.as_slice(), subject.subject_type.as_str()) {
+        (["pulls", number], _) => format!("pull/{}", number),
+        (["issues", number], _) => format!("issues/{}", number),
+        (["commits", sha], _) => format!("commit/{}", sha),
+        (_, "Release") => "releases".to_string(),
+        (_, "Discussion") => "discussions".to_string(),
+        (_, "CheckSuite") => "actions".to_string(),
+        _ => return repo.html_url.clone(),
+    };
+    format!("{}/{}", repo.html_url, page)
+}
+
+impl From<ApiNotification> for Notification {
+    fn from(n: ApiNotification) -> Self {
+        Notification {
+            html_url: subject_html_url(&n.subject, &n.repository),
+            reason: n.reason.replace('_', " "),
+            repo: n.repository.full_name,
+            subject_type: n.subject.subject_type,
+            title: n.subject.title,
+            unread: n.unread,
+            updated_at: n.updated_at,
+        }
+    }
+}
+
+/// Pages of notifications fetched at most, fifty a page
+const MAX_PAGES: usize = 20;
+
+/// Notifications from `url`, the first page, and the pages its `Link`
+/// headers lead to
+async fn fetch_notifications(client: &reqwest::Client, headers: &HeaderMap, url: &str) -> Result<Vec<Notification>> {
+    let mut notifications = Vec::new();
+    let mut next = Some(url.to_string());
+    let mut pages = 0;
+    while let Some(url) = next {
+        if pages == MAX_PAGES {
+            warn!("Note: Showing the first {} notifications; mark some as read to see older ones", notifications.len());
+            break;
+        }
+        let response = client
+            .get(&url)
+            .headers(headers.clone())
+            .send_retrying()
+            .await
+            .context("Failed to fetch notifications")?;
+        crate::ratelimit::record(response.headers());
+        match response.status() {
+            status if status.is_success() => {}
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => anyhow::bail!(
+                "GitHub refused to list notifications ({}); the token needs the notifications or repo scope",
+                response.status()),
+            status => anyhow::bail!("Failed to fetch notifications ({})", status),
+        }
+        let link = response.headers().get(reqwest::header::LINK).and_then(|v| v.to_str().ok());
+        next = PageLinks::parse(link).next;
+        let page_notifications: Vec<ApiNotification> = response.json().await
+            .context("Failed to parse notifications response")?;
+        notifications.extend(page_notifications.into_iter().map(Notification::from));
+        pages += 1;
+    }
+    Ok(notifications)
+}
+
+fn render_table(notifications: &[Notification], timezone: DisplayZone, all: bool) -> String {
+    if notifications.is_empty() {
+        return if all { "No notifications.\n" } else { "No unread notifications.\n" }.to_string();
+    }
+
+    let rows: Vec<Vec<String>> = notifications.iter()
+        .map(|n| {
+            let mut row = vec![
+                timezone.format(n.updated_at, "%Y-%m-%d %H:%M"),
+                n.reason.clone(),
+                n.repo.clone(),
+                n.title.clone(),
+                n.html_url.clone(),
+            ];
+            // Without --all everything listed is unread
+            if all {
+                row.insert(0, if n.unread { "*" } else { "" }.to_string());
+            }
+            row
+        })
+        .collect();
+    let mut headers = vec!["UPDATED", "REASON", "REPOSITORY", "TITLE", "URL"];
+    if all {
+        headers.insert(0, "UNREAD");
+    }
+    plain_table(&headers, &rows)
+}
+
+pub async fn run(args: &NotificationsArgs) -> Result<()> {
+    let (client, headers) = crate::setup_github_client()?;
+    if !headers.contains_key(AUTHORIZATION) {
+        anyhow::bail!("Notifications are private; set GH_TOKEN or run `wiwo auth login`");
+    }
+
+    // The API lists the most recently updated first
+    let url = format!("{}/notifications?all={}&participating={}&per_page=50",
+        crate::config::api_base(), args.all, args.participating);
+    let notifications = fetch_notifications(&client, &headers, &url).await?;
+
+    match args.format {
+        NotificationsFormat::Table => print!("{}", render_table(&notifications, args.timezone, args.all)),
+        NotificationsFormat::Json => println!("{}", serde_json::to_string_pretty(&notifications)?),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server;
+
+    fn html_url(subject_type: &str, url: Option<&str>) -> String {
+        let subject = Subject { title: String::new(), url: url.map(str::to_string), subject_type: subject_type.to_string() };
+        let repo = NotificationRepository { full_name: "octo/hello".to_string(), html_url: "https://github.com/octo/hello".to_string() };
+        subject_html_url(&subject, &repo)
+    }
+
+    #[test]
+    fn subjects_link_to_their_web_pages() {
+        let api = "https://api.github.com/repos/octo/hello";
+        assert_eq!(html_url("PullRequest", Some(&format!("{api}/pulls/12"))), "https://github.com/octo/hello/pull/12");
+        assert_eq!(html_url("Issue", Some(&format!("{api}/issues/7"))), "https://github.com/octo/hello/issues/7");
+        assert_eq!(html_url("Commit", Some(&format!("{api}/commits/abc123"))), "https://github.com/octo/hello/commit/abc123");
+        // Releases are linked by an id the web doesn't use
+        assert_eq!(html_url("Release", Some(&format!("{api}/releases/123456"))), "https://github.com/octo/hello/releases");
+        assert_eq!(html_url("Discussion", None), "https://github.com/octo/hello/discussions");
+        assert_eq!(html_url("CheckSuite", None), "https://github.com/octo/hello/actions");
+    }
+
+    #[test]
+    fn subjects_without_a_known_page_link_to_the_repository() {
+        assert_eq!(html_url("Issue", None), "https://github.com/octo/hello");
+        assert_eq!(html_url("RepositoryVulnerabilityAlert", Some("https://api.github.com/repos/octo/hello/vulnerability-alerts/3")),
+            "https://github.com/octo/hello");
+        // Another repository's URL isn't mistaken for this one's
+        assert_eq!(html_url("PullRequest", Some("https://api.github.com/repos/octo/other/pulls/12")), "https://github.com/octo/hello");
+    }
+
+    #[test]
+    fn subjects_on_enterprise_servers_link_to_their_web_pages() {
+        let subject = Subject {
+            title: String::new(),
+            url: Some("https://ghe.example.com/api/v3/repos/octo/hello/pulls/12".to_string()),
+            subject_type: "PullRequest".to_string(),
+        };
+        let repo = NotificationRepository { full_name: "octo/hello".to_string(), html_url: "https://ghe.example.com/octo/hello".to_string() };
+        assert_eq!(subject_html_url(&subject, &repo), "https://ghe.example.com/octo/hello/pull/12");
+    }
+
+    #[tokio::test]
+    async fn pages_follow_the_link_header() {
+        let (url, requests) = test_server::start(|request| {
+            let page: u32 = request.uri().query().unwrap_or_default().split('&')
+                .find_map(|pair| pair.strip_prefix("page=")?.parse().ok())
+                .unwrap_or(1);
+            let body = serde_json::json!([{
+                "reason": "review_requested", "unread": true, "updated_at": "2025-03-01T09:00:00Z",
+                "subject": { "title": format!("Page {page}"), "url": null, "type": "PullRequest" },
+                "repository": { "full_name": "octo/hello", "html_url": "https://github.com/octo/hello" }
+            }]);
+            let mut response = test_server::response(hyper::StatusCode::OK, &body.to_string());
+            if page < 3 {
+                let host = request.headers()["host"].to_str().unwrap();
+                let link = format!(r#"<http://{host}/notifications?per_page=50&page={}>; rel="next""#, page + 1);
+                response.headers_mut().insert("link", link.parse().unwrap());
+            }
+            Some(response)
+        });
+        let notifications = fetch_notifications(&reqwest::Client::new(), &HeaderMap::new(), &format!("{url}/notifications?per_page=50"))
+            .await
+            .unwrap();
+        let titles: Vec<&str> = notifications.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(titles, ["Page 1", "Page 2", "Page 3"]);
+        assert_eq!(notifications[0].reason, "review requested");
+        // No request for an empty page after the last
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn paging_stops_at_the_maximum() {
+        let (url, requests) = test_server::start(|request| {
+            let mut response = test_server::response(hyper::StatusCode::OK, "[]");
+            let link = format!(r#"<http://{}/notifications?page=next>; rel="next""#, request.headers()["host"].to_str().unwrap());
+            response.headers_mut().insert("link", link.parse().unwrap());
+            Some(response)
+        });
+        fetch_notifications(&reqwest::Client::new(), &HeaderMap::new(), &format!("{url}/notifications")).await.unwrap();
+        assert_eq!(requests.lock().unwrap().len(), MAX_PAGES);
+    }
+}
@@ -0,0 +1,141 @@
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::output::pad_to_width;
+use crate::timezone::{self, DisplayZone};
+use crate::Event;
+
+#[derive(clap::Args)]
+pub struct OrgsArgs {
+    /// GitHub username (defaults to authenticated user if GH_TOKEN is set)
+    #[arg(short, long)]
+    user: Option<String>,
+    /// Time range to cover (e.g., "90d", "2w", "last-month"); limited to what the Events API returns
+    #[arg(short, long, default_value = "90d", value_parser = crate::parse_time_arg)]
+    time: String,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OrgsFormat::Table)]
+    format: OrgsFormat,
+    /// Order of owners
+    #[arg(long, value_enum, default_value_t = OrgsSort::Count)]
+    sort: OrgsSort,
+    /// Number of repositories to list per owner
+    #[arg(long, default_value_t = 3)]
+    top: usize,
+    /// Time zone for evaluating keyword ranges like "last-month": an IANA name, or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OrgsFormat {
+    Table,
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OrgsSort {
+    /// Most events first
+    Count,
+    /// Alphabetically by owner
+    Name,
+}
+
+#[derive(Debug, Serialize)]
+struct OwnerActivity {
+    owner: String,
+    events: usize,
+    /// Share of all events, in percent
+    percent: f64,
+    top_repos: Vec<RepoActivity>,
+}
+
+#[derive(Debug, Serialize)]
+struct RepoActivity {
+    repo: String,
+    events: usize,
+}
+
+/// Events per repository owner. Owners differing only in case are the same
+/// account on GitHub, so they're grouped together under the first spelling seen.
+fn by_owner(events: &[Event], top: usize, sort: OrgsSort) -> Vec<OwnerActivity> {
+    let mut owners: Vec<(String, HashMap<&str, usize>)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for event in events {
+        let owner = event.repo.owner();
+        let i = *index.entry(owner.to_lowercase()).or_insert_with(|| {
+            owners.push((owner.to_string(), HashMap::new()));
+            owners.len() - 1
+        });
+        *owners[i].1.entry(&event.repo.name).or_default() += 1;
+    }
+
+    let total = events.len().max(1) as f64;
+    let mut activity: Vec<OwnerActivity> = owners.into_iter()
+        .map(|(owner, repos)| {
+            let events: usize = repos.values().sum();
+            let mut top_repos: Vec<RepoActivity> = repos.into_iter()
+                .map(|(repo, events)| RepoActivity { repo: repo.to_string(), events })
+                .collect();
+            top_repos.sort_by(|a, b| b.events.cmp(&a.events).then_with(|| a.repo.cmp(&b.repo)));
+            top_repos.truncate(top);
+            OwnerActivity { owner, events, percent: events as f64 * 100.0 / total, top_repos }
+        })
+        .collect();
+
+    match sort {
+        OrgsSort::Count => activity.sort_by(|a, b| b.events.cmp(&a.events)
+            .then_with(|| a.owner.to_lowercase().cmp(&b.owner.to_lowercase()))),
+        OrgsSort::Name => activity.sort_by_key(|a| a.owner.to_lowercase()),
+    }
+    activity
+}
+
+fn render_table(activity: &[OwnerActivity], username: &str, time_range: &str) -> String {
+    if activity.is_empty() {
+        return "No events found.\n".to_string();
+    }
+
+    let mut out = String::new();
+    writeln!(out, "Activity by owner for {} ({})\n", username, time_range).unwrap();
+
+    // Repositories are indented under their owner, so size the name column for both
+    let width = activity.iter()
+        .flat_map(|a| std::iter::once(a.owner.len()).chain(a.top_repos.iter().map(|r| r.repo.len() + 2)))
+        .chain(["OWNER".len()])
+        .max()
+        .unwrap_or(0);
+    let count_width = activity.iter().map(|a| a.events.to_string().len()).chain([6]).max().unwrap_or(0);
+
+    writeln!(out, "{}  {:>count_width$}  SHARE", pad_to_width("OWNER", width), "EVENTS", count_width = count_width).unwrap();
+    for owner in activity {
+        writeln!(out, "{}  {:>count_width$}  {:>5.1}%", pad_to_width(&owner.owner, width), owner.events, owner.percent, count_width = count_width).unwrap();
+        for repo in &owner.top_repos {
+            writeln!(out, "{}  {:>count_width$}", pad_to_width(&format!("  {}", repo.repo), width), repo.events, count_width = count_width).unwrap();
+        }
+    }
+
+    out
+}
+
+pub async fn run(args: &OrgsArgs) -> Result<()> {
+    let now = Utc::now();
+    let (since, until) = crate::parse_time_range(&args.time, now, args.timezone)?;
+    let until = until.unwrap_or(now);
+
+    let (client, headers) = crate::setup_github_client()?;
+    let username = crate::resolve_username(&client, &headers, args.user.as_deref()).await?;
+
+    let events = crate::fetch_events_in_range(&client, &headers, &username, since..=until).await?;
+
+    let activity = by_owner(&events, args.top, args.sort);
+    match args.format {
+        OrgsFormat::Table => print!("{}", render_table(&activity, &username, &args.time)),
+        OrgsFormat::Json => println!("{}", serde_json::to_string_pretty(&activity)?),
+    }
+
+    Ok(())
+}
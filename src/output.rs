@@ -0,0 +1,1061 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::io::IsTerminal;
+use std::path::Path;
+
+use chrono::{DateTime, Datelike, Duration, Utc};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::color;
+use crate::config;
+use crate::heatmap;
+use crate::summary::Summary;
+use crate::timezone::DisplayZone;
+use crate::{Commit, Event, OutputFormat};
+
+/// Details about the query that produced the events, for titles and headers
+pub struct RenderContext<'a> {
+    pub username: &'a str,
+    /// Human-readable description of the range, e.g. "last 30d"
+    pub time_range: &'a str,
+    pub columns: &'a [Column],
+    pub time_format: TimeFormat,
+    pub timezone: DisplayZone,
+    pub color: bool,
+    /// Maximum table width; long repo names and URLs are truncated to fit
+    pub max_width: Option<usize>,
+    /// Render repository names as clickable OSC 8 hyperlinks
+    pub hyperlinks: bool,
+    /// Insert a separator row before each day or week of a table
+    pub group_by: Option<GroupBy>,
+    /// Totals appended to table output and embedded in JSON
+    pub summary: Option<&'a Summary>,
+    /// Start of the requested range
+    pub since: DateTime<Utc>,
+    /// End of the requested range, for ones that don't run to now
+    pub until: Option<DateTime<Utc>>,
+    /// Print a sparkline of daily activity above the table
+    pub sparkline: bool,
+    pub table_style: TableStyle,
+    /// Whether filters may have removed events
+    pub filtered: bool,
+}
+
+impl RenderContext<'_> {
+    /// A timestamp as `--time-format` says, leaving out what the separator of
+    /// `group`, if any, already shows
+    fn format_time_in_group(&self, time: DateTime<Utc>, group: Option<GroupBy>) -> String {
+        match self.time_format {
+            TimeFormat::Absolute => match group {
+                Some(GroupBy::Day) => self.timezone.format(time, "%H:%M:%S"),
+                Some(GroupBy::Week) => self.timezone.format(time, "%a %H:%M:%S"),
+                None => self.timezone.format(time, "%Y-%m-%d %H:%M:%S"),
+            },
+            TimeFormat::Iso => self.timezone.format_rfc3339(time),
+            TimeFormat::Relative => format_relative(Utc::now() - time),
+        }
+    }
+}
+
+/// Borders and separators used to draw the events table
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TableStyle {
+    /// `|` and `-` separators
+    Ascii,
+    /// Box-drawing borders
+    Unicode,
+    /// Columns separated by two spaces, no rules
+    Compact,
+}
+
+/// Horizontal rules of a table
+#[derive(Clone, Copy)]
+enum Rule {
+    Top,
+    Middle,
+    Bottom,
+}
+
+impl TableStyle {
+    fn bordered(self) -> bool {
+        self == TableStyle::Unicode
+    }
+
+    /// Join already padded cells into a row
+    fn row(self, cells: &[String]) -> String {
+        match self {
+            TableStyle::Ascii => cells.join(" | "),
+            TableStyle::Unicode => format!("│ {} │", cells.join(" │ ")),
+            TableStyle::Compact => cells.join("  "),
+        }
+    }
+
+    fn rule(self, widths: &[usize], rule: Rule) -> Option<String> {
+        let line = |fill: &str, left: &str, join: &str, right: &str| {
+            let segments: Vec<String> = widths.iter().map(|&w| fill.repeat(w)).collect();
+            format!("{}{}{}", left, segments.join(join), right)
+        };
+        match (self, rule) {
+            (TableStyle::Ascii, Rule::Middle) => Some(line("-", "", "-+-", "")),
+            (TableStyle::Unicode, Rule::Top) => Some(line("─", "┌─", "─┬─", "─┐")),
+            (TableStyle::Unicode, Rule::Middle) => Some(line("─", "├─", "─┼─", "─┤")),
+            (TableStyle::Unicode, Rule::Bottom) => Some(line("─", "└─", "─┴─", "─┘")),
+            _ => None,
+        }
+    }
+
+    /// Width taken by separators and borders for `columns` columns
+    fn overhead(self, columns: usize) -> usize {
+        let gaps = columns.saturating_sub(1);
+        match self {
+            TableStyle::Ascii => 3 * gaps,
+            TableStyle::Unicode => 3 * gaps + 4,
+            TableStyle::Compact => 2 * gaps,
+        }
+    }
+}
+
+/// Period used to group table rows
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GroupBy {
+    Day,
+    /// ISO week, starting on Monday
+    Week,
+}
+
+impl GroupBy {
+    fn label(self, time: DateTime<Utc>, timezone: DisplayZone) -> String {
+        let local = timezone.naive_local(time);
+        match self {
+            GroupBy::Day => local.format("%Y-%m-%d").to_string(),
+            GroupBy::Week => {
+                let week = local.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+        }
+    }
+}
+
+/// How timestamps are displayed in the events table
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TimeFormat {
+    /// 2025-01-07 14:33:02
+    Absolute,
+    /// 2 hours ago
+    Relative,
+    /// RFC 3339
+    Iso,
+}
+
+fn format_relative(elapsed: Duration) -> String {
+    fn plural(n: i64, unit: &str) -> String {
+        if n == 1 { format!("1 {} ago", unit) } else { format!("{} {}s ago", n, unit) }
+    }
+
+    if elapsed < Duration::minutes(1) {
+        "just now".to_string()
+    } else if elapsed < Duration::hours(1) {
+        plural(elapsed.num_minutes(), "minute")
+    } else if elapsed < Duration::days(1) {
+        plural(elapsed.num_hours(), "hour")
+    } else {
+        plural(elapsed.num_days(), "day")
+    }
+}
+
+/// A column of the events table
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Column {
+    Timestamp,
+    Event,
+    Repo,
+    Visibility,
+    Url,
+    Actor,
+    /// User whose activity the event is, shown by default with several --user
+    User,
+    /// Number and title of the pull request or issue, when there is one
+    Details,
+}
+
+impl Column {
+    /// Columns shown when `--columns` isn't given
+    pub const DEFAULT: &'static [Column] = &[
+        Column::Timestamp,
+        Column::Event,
+        Column::Repo,
+        Column::Visibility,
+        Column::Url,
+    ];
+
+    pub fn header(self) -> &'static str {
+        match self {
+            Column::Timestamp => "TIMESTAMP",
+            Column::Event => "EVENT",
+            Column::Repo => "REPOSITORY",
+            Column::Visibility => "VISIBILITY",
+            Column::Url => "URL",
+            Column::Actor => "ACTOR",
+            Column::User => "USER",
+            Column::Details => "DETAILS",
+        }
+    }
+
+    pub fn cell(self, event: &Event, context: &RenderContext) -> String {
+        self.grouped_cell(event, context, None)
+    }
+
+    /// The cell in a table with a separator before each `group`
+    fn grouped_cell(self, event: &Event, context: &RenderContext, group: Option<GroupBy>) -> String {
+        match self {
+            Column::Timestamp => context.format_time_in_group(event.created_at, group),
+            Column::Event => event.formatted_type(),
+            Column::Repo => event.repo.name.clone(),
+            Column::Visibility => visibility(event).to_string(),
+            Column::Url => event.repo.html_url(),
+            Column::Actor => event.actor_login().to_string(),
+            Column::User => event.user.clone().unwrap_or_default(),
+            Column::Details => event.details(),
+        }
+    }
+
+    /// Apply ANSI styling to an already padded cell
+    fn paint(self, cell: &str, event: &Event) -> String {
+        match self {
+            Column::Event => color::event_type(cell, &event.formatted_type()),
+            Column::Repo | Column::Visibility if event.repo.private.unwrap_or(false) => color::private(cell),
+            _ => cell.to_string(),
+        }
+    }
+
+    /// Display width of the column, only measuring cells where it varies
+    fn width(self, events: &[Event], context: &RenderContext, group: Option<GroupBy>) -> usize {
+        match self {
+            Column::Timestamp => events.iter()
+                .map(|e| context.format_time_in_group(e.created_at, group).width())
+                .max()
+                .unwrap_or(0)
+                .max(9), // Width of the TIMESTAMP header
+            Column::Event => events.iter()
+                .map(|e| e.formatted_type().width())
+                .max()
+                .unwrap_or(0)
+                .max(10), // Minimum width of 10 for event type
+            Column::Repo => events.iter()
+                .map(|e| e.repo.name.width())
+                .max()
+                .unwrap_or(0)
+                .max(10), // Minimum width of 10 for repo name
+            Column::Visibility => 10,
+            Column::Url => 20,
+            Column::Actor => events.iter()
+                .map(|e| e.actor_login().width())
+                .max()
+                .unwrap_or(0)
+                .max(5), // Width of the ACTOR header
+            Column::User => events.iter()
+                .map(|e| e.user.as_deref().unwrap_or("").width())
+                .max()
+                .unwrap_or(0)
+                .max(4), // Width of the USER header
+            Column::Details => events.iter()
+                .map(|e| e.details().width())
+                .max()
+                .unwrap_or(0)
+                .max(7), // Width of the DETAILS header
+        }
+    }
+}
+
+/// Render events in the requested format
+pub fn render(format: OutputFormat, events: &[Event], context: &RenderContext) -> Result<String> {
+    let summary = context.summary.map(Summary::render_text).unwrap_or_default();
+
+    match format {
+        OutputFormat::Table => {
+            let sparkline = if context.sparkline && !events.is_empty() {
+                heatmap::sparkline(events, context)
+            } else {
+                String::new()
+            };
+            Ok(sparkline + &render_table(events, context) + &summary)
+        }
+        OutputFormat::Json => render_json(events, context.summary, context.columns.contains(&Column::Visibility)),
+        OutputFormat::Markdown => Ok(render_markdown(events, context) + &summary),
+        OutputFormat::Html => Ok(render_html(events, context)),
+        OutputFormat::Ics => Ok(render_ics(events)),
+        OutputFormat::Atom => Ok(render_atom(events, context)),
+        OutputFormat::Heatmap => Ok(heatmap::render(events, context)),
+        OutputFormat::Ndjson => unreachable!("ndjson output is streamed"),
+        OutputFormat::Sqlite => unreachable!("sqlite output is written directly to the database"),
+    }
+}
+
+/// Write to a temp file next to `path` and rename it into place. An existing
+/// file keeps its permissions; a new one gets the usual ones for the umask.
+pub fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    write_through_temp_file(path, contents, false)
+}
+
+/// Like `write_atomic`, but readable by the owner only, for tokens and cached activity
+pub fn write_private(path: &Path, contents: &str) -> Result<()> {
+    write_through_temp_file(path, contents, true)
+}
+
+fn write_through_temp_file(path: &Path, contents: &str, private: bool) -> Result<()> {
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    if !parent.is_dir() {
+        anyhow::bail!("Output directory {} does not exist", parent.display());
+    }
+
+    // Temp files are created readable by the owner only, which suits private files
+    let mut builder = tempfile::Builder::new();
+    #[cfg(unix)]
+    if !private {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(std::fs::Permissions::from_mode(0o666));
+    }
+    let mut file = builder.tempfile_in(parent)
+        .context(format!("Failed to create temporary file in {}", parent.display()))?;
+    if !private && let Ok(metadata) = std::fs::metadata(path) {
+        file.as_file().set_permissions(metadata.permissions())
+            .context(format!("Failed to keep the permissions of {}", path.display()))?;
+    }
+    std::io::Write::write_all(&mut file, contents.as_bytes())
+        .context("Failed to write output")?;
+    file.persist(path)
+        .context(format!("Failed to write {}", path.display()))?;
+
+    Ok(())
+}
+
+/// Pad with spaces to a display width, so wide (CJK, emoji) and zero-width
+/// (combining) characters keep columns aligned
+pub fn pad_to_width(s: &str, width: usize) -> String {
+    pad_display_to_width(s, s.width(), width)
+}
+
+/// Borderless table with a header row for the list subcommands. Columns are
+/// separated by two spaces and the last one is left unpadded, so put
+/// free text like titles there.
+pub fn plain_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|i| rows.iter().map(|row| row[i].width()).chain([headers[i].width()]).max().unwrap_or(0))
+        .collect();
+
+    let mut out = String::new();
+    let mut write_row = |cells: &[&str]| {
+        let line: Vec<String> = cells.iter().zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| if i + 1 == cells.len() { cell.to_string() } else { pad_to_width(cell, width) })
+            .collect();
+        writeln!(out, "{}", line.join("  ")).unwrap();
+    };
+    write_row(headers);
+    for row in rows {
+        write_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+    out
+}
+
+/// Pad a string whose visible width was measured separately, e.g. one
+/// containing escape sequences that take up no space on screen
+fn pad_display_to_width(display: &str, measured_width: usize, width: usize) -> String {
+    if measured_width >= width {
+        display.to_string()
+    } else {
+        format!("{}{}", display, " ".repeat(width - measured_width))
+    }
+}
+
+/// Wrap text in an OSC 8 terminal hyperlink
+fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// Whether the terminal is known to render OSC 8 hyperlinks
+pub fn terminal_supports_hyperlinks() -> bool {
+    if !std::io::stdout().is_terminal() {
+        return false;
+    }
+    let env = |name: &str| std::env::var(name).unwrap_or_default();
+
+    matches!(env("TERM_PROGRAM").as_str(), "iTerm.app" | "WezTerm" | "vscode" | "Hyper")
+        || env("VTE_VERSION").parse::<u32>().is_ok_and(|v| v >= 5000)
+        || !env("WT_SESSION").is_empty()
+        || !env("KITTY_WINDOW_ID").is_empty()
+        || env("TERM").contains("kitty")
+        || env("TERM").contains("foot")
+}
+
+/// "Unknown" when the repository couldn't be looked up, e.g. with a token that can't see it
+pub fn visibility(event: &Event) -> &'static str {
+    match event.repo.private {
+        Some(true) => "Private",
+        Some(false) => "Public",
+        None => "Unknown",
+    }
+}
+
+fn no_events(context: &RenderContext) -> String {
+    if context.filtered {
+        "No events found (filters were active).\n".to_string()
+    } else {
+        "No events found.\n".to_string()
+    }
+}
+
+/// `event` as JSON, without the "private" field unless visibility is shown
+pub fn event_json(event: &Event, show_visibility: bool) -> serde_json::Result<serde_json::Value> {
+    let mut value = serde_json::to_value(event)?;
+    if !show_visibility && let Some(repo) = value.get_mut("repo").and_then(serde_json::Value::as_object_mut) {
+        repo.remove("private");
+    }
+    Ok(value)
+}
+
+pub fn render_json(events: &[Event], summary: Option<&Summary>, show_visibility: bool) -> Result<String> {
+    let events = events.iter()
+        .map(|e| event_json(e, show_visibility))
+        .collect::<serde_json::Result<Vec<_>>>()?;
+    let json = match summary {
+        Some(summary) => serde_json::to_string_pretty(&serde_json::json!({
+            "events": events,
+            "summary": summary,
+        }))?,
+        None => serde_json::to_string_pretty(&events)?,
+    };
+    Ok(format!("{}\n", json))
+}
+
+/// The columns of formats that link repository names, where a URL column
+/// would be redundant
+fn linked_columns(context: &RenderContext) -> Vec<Column> {
+    if context.columns.contains(&Column::Repo) {
+        context.columns.iter().copied().filter(|c| *c != Column::Url).collect()
+    } else {
+        context.columns.to_vec()
+    }
+}
+
+fn render_markdown(events: &[Event], context: &RenderContext) -> String {
+    if events.is_empty() {
+        return no_events(context);
+    }
+
+    let columns = linked_columns(context);
+    let mut out = String::new();
+    let headers: Vec<&str> = columns.iter().map(|c| c.header()).collect();
+    writeln!(out, "| {} |", headers.join(" | ")).unwrap();
+    writeln!(out, "|{}", "---|".repeat(columns.len())).unwrap();
+
+    for event in events {
+        let cells: Vec<String> = columns.iter().map(|column| match column {
+            Column::Repo => format!("[{}]({})", event.repo.name, event.repo.html_url()),
+            // A pipe would end the cell
+            _ => column.cell(event, context).replace('|', "\\|"),
+        }).collect();
+        writeln!(out, "| {} |", cells.join(" | ")).unwrap();
+    }
+
+    out
+}
+
+/// Column widths of an events table, kept by `--watch` so rows appended later
+/// line up with the table already printed
+pub struct TableLayout {
+    columns: Vec<Column>,
+    widths: Vec<usize>,
+    /// Truncation cap for each column, to fit `--max-width`
+    caps: Vec<Option<usize>>,
+    /// The last column is only padded when followed by a border
+    pad_last: bool,
+    /// Grouping shown by separator rows, which timestamps needn't repeat
+    group: Option<GroupBy>,
+}
+
+impl TableLayout {
+    pub fn new(events: &[Event], context: &RenderContext, group: Option<GroupBy>) -> Self {
+        // With clickable repository names the URL column is redundant
+        let columns = if context.hyperlinks { linked_columns(context) } else { context.columns.to_vec() };
+
+        // Find the width of each selected column
+        let mut widths: Vec<usize> = columns.iter()
+            .map(|column| column.width(events, context, group))
+            .collect();
+        let style = context.table_style;
+        let caps = match context.max_width {
+            Some(max_width) => fit_columns(&columns, &mut widths, events, max_width, style.overhead(columns.len())),
+            None => vec![None; columns.len()],
+        };
+
+        // A right border needs the last column padded to its real width
+        if style.bordered() && columns.last() == Some(&Column::Url) {
+            let last = columns.len() - 1;
+            let longest = events.iter().map(|e| e.repo.html_url().width()).max().unwrap_or(0);
+            widths[last] = caps[last].map_or(longest, |cap| longest.min(cap)).max(Column::Url.header().len());
+        }
+
+        TableLayout { columns, widths, caps, pad_last: style.bordered(), group }
+    }
+
+    /// The header row, bold when coloring
+    pub fn header(&self, context: &RenderContext) -> String {
+        let header: Vec<String> = self.columns.iter().zip(&self.widths)
+            .enumerate()
+            .map(|(i, (column, &width))| {
+                if i + 1 == self.columns.len() && !self.pad_last { column.header().to_string() } else { pad_to_width(column.header(), width) }
+            })
+            .collect();
+        let header = context.table_style.row(&header);
+        if context.color { color::bold(&header) } else { header }
+    }
+
+    /// One event's row, without a trailing newline
+    pub fn row(&self, event: &Event, context: &RenderContext) -> String {
+        let cells: Vec<String> = self.columns.iter().zip(&self.widths)
+            .enumerate()
+            .map(|(i, (column, &width))| {
+                let cell = column.grouped_cell(event, context, self.group);
+                let cell = match self.caps[i] {
+                    Some(cap) => truncate_middle(&cell, cap),
+                    None => cell,
+                };
+                // Measure the visible text before adding any link escapes
+                let measured_width = cell.width();
+                let cell = if context.hyperlinks && *column == Column::Repo {
+                    hyperlink(&event.repo.html_url(), &cell)
+                } else {
+                    cell
+                };
+                // Without a right border, the last column isn't padded to avoid trailing whitespace
+                let cell = if i + 1 == self.columns.len() && !self.pad_last { cell } else { pad_display_to_width(&cell, measured_width, width) };
+                // Color after padding so escape codes don't count towards the width
+                if context.color { column.paint(&cell, event) } else { cell }
+            })
+            .collect();
+        context.table_style.row(&cells)
+    }
+}
+
+fn render_table(events: &[Event], context: &RenderContext) -> String {
+    if events.is_empty() {
+        return no_events(context);
+    }
+
+    let layout = TableLayout::new(events, context, context.group_by);
+    let style = context.table_style;
+    let mut out = String::new();
+
+    if let Some(rule) = style.rule(&layout.widths, Rule::Top) {
+        writeln!(out, "{}", rule).unwrap();
+    }
+
+    writeln!(out, "{}", layout.header(context)).unwrap();
+
+    if let Some(rule) = style.rule(&layout.widths, Rule::Middle) {
+        writeln!(out, "{}", rule).unwrap();
+    }
+
+    // Events
+    let mut current_group = None;
+    for (index, event) in events.iter().enumerate() {
+        if let Some(group_by) = context.group_by {
+            let label = group_by.label(event.created_at, context.timezone);
+            if current_group.as_ref() != Some(&label) {
+                let count = events[index..].iter()
+                    .take_while(|e| group_by.label(e.created_at, context.timezone) == label)
+                    .count();
+                let noun = if count == 1 { "event" } else { "events" };
+                writeln!(out, "── {} ({} {}) ──", label, count, noun).unwrap();
+                current_group = Some(label);
+            }
+        }
+
+        writeln!(out, "{}", layout.row(event, context)).unwrap();
+    }
+
+    if let Some(rule) = style.rule(&layout.widths, Rule::Bottom) {
+        writeln!(out, "{}", rule).unwrap();
+    }
+
+    out
+}
+
+/// Print to stdout, going through `$PAGER` (default `less -FRX`) when stdout
+/// is a terminal and the output is taller than it
+pub fn print_paged(rendered: &str, use_pager: bool) -> Result<()> {
+    let height = terminal_size::terminal_size_of(std::io::stdout()).map(|(_, h)| h.0 as usize);
+    let too_tall = height.is_some_and(|h| rendered.lines().count() >= h);
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less -FRX".to_string());
+    let mut parts = pager.split_whitespace();
+
+    if use_pager && too_tall && let Some(program) = parts.next() && program != "cat" {
+        let child = std::process::Command::new(program)
+            .args(parts)
+            .stdin(std::process::Stdio::piped())
+            .spawn();
+
+        // Fall back to plain output if the pager can't be started
+        if let Ok(mut child) = child {
+            if let Some(mut stdin) = child.stdin.take() {
+                // The pager closing early (e.g. quitting less) is not an error
+                let _ = std::io::Write::write_all(&mut stdin, rendered.as_bytes());
+            }
+            child.wait().context(format!("Failed to run pager {}", program))?;
+            return Ok(());
+        }
+    }
+
+    print!("{}", rendered);
+    Ok(())
+}
+
+/// Width of the terminal attached to stdout, if any
+pub fn terminal_width() -> Option<usize> {
+    terminal_size::terminal_size_of(std::io::stdout()).map(|(width, _)| width.0 as usize)
+}
+
+/// Shrink the repository, URL and details columns so the table fits in `max_width`.
+/// Returns the truncation cap for each column.
+fn fit_columns(columns: &[Column], widths: &mut [usize], events: &[Event], max_width: usize, overhead: usize) -> Vec<Option<usize>> {
+    let mut caps = vec![None; columns.len()];
+    let shrinkable = |column: &Column| matches!(column, Column::Repo | Column::Url | Column::Details);
+
+    // The URL column is unpadded, so its real width is that of the longest URL
+    let natural: Vec<usize> = columns.iter().zip(widths.iter())
+        .map(|(column, &width)| match column {
+            Column::Url => events.iter().map(|e| e.repo.html_url().width()).max().unwrap_or(0).max(width),
+            _ => width,
+        })
+        .collect();
+
+    if natural.iter().sum::<usize>() + overhead <= max_width {
+        return caps;
+    }
+
+    let fixed: usize = columns.iter().zip(&natural)
+        .filter(|(column, _)| !shrinkable(column))
+        .map(|(_, width)| width)
+        .sum();
+    let mut available = max_width.saturating_sub(fixed + overhead);
+
+    // Share the remaining space, letting narrow columns give up what they don't need
+    let mut order: Vec<usize> = (0..columns.len()).filter(|&i| shrinkable(&columns[i])).collect();
+    order.sort_by_key(|&i| natural[i]);
+    let mut remaining = order.len();
+    for i in order {
+        let share = (available / remaining).max(10);
+        let cap = natural[i].min(share);
+        caps[i] = Some(cap);
+        widths[i] = widths[i].min(cap);
+        available = available.saturating_sub(cap);
+        remaining -= 1;
+    }
+
+    caps
+}
+
+/// Shorten to `max` display columns by replacing the middle with an ellipsis,
+/// keeping both ends (e.g. `my-org/…-service`) and whole grapheme clusters
+fn truncate_middle(s: &str, max: usize) -> String {
+    if s.width() <= max {
+        return s.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+
+    let budget = max - 1; // one column for the ellipsis
+    let head_budget = budget.div_ceil(2);
+    let tail_budget = budget - head_budget;
+    let graphemes: Vec<&str> = s.graphemes(true).collect();
+
+    let mut head = String::new();
+    let mut head_width = 0;
+    let mut head_end = 0;
+    for (i, g) in graphemes.iter().enumerate() {
+        if head_width + g.width() > head_budget {
+            break;
+        }
+        head.push_str(g);
+        head_width += g.width();
+        head_end = i + 1;
+    }
+
+    let mut tail = Vec::new();
+    let mut tail_width = 0;
+    for g in graphemes[head_end..].iter().rev() {
+        if tail_width + g.width() > tail_budget {
+            break;
+        }
+        tail.push(*g);
+        tail_width += g.width();
+    }
+    tail.reverse();
+
+    format!("{}…{}", head, tail.concat())
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_html(events: &[Event], context: &RenderContext) -> String {
+    let mut type_counts: BTreeMap<String, usize> = BTreeMap::new();
+    let mut repo_counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for event in events {
+        *type_counts.entry(event.formatted_type()).or_default() += 1;
+        *repo_counts.entry(&event.repo.name).or_default() += 1;
+    }
+
+    // Most active first
+    let mut type_counts: Vec<_> = type_counts.into_iter().collect();
+    type_counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    let mut repo_counts: Vec<_> = repo_counts.into_iter().collect();
+    repo_counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    let mut out = String::new();
+    out.push_str(r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>GitHub activity report</title>
+<style>
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2em; color: #24292f; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #d0d7de; padding: 4px 10px; text-align: left; }
+th { background: #f6f8fa; }
+tr:nth-child(even) td { background: #fafbfc; }
+td.count { text-align: right; }
+.private { color: #cf222e; }
+</style>
+</head>
+<body>
+<h1>GitHub activity report</h1>
+"#);
+    writeln!(out, "<p>{} events</p>", events.len()).unwrap();
+
+    out.push_str("<h2>By event type</h2>\n<table>\n<tr><th>Event</th><th>Count</th></tr>\n");
+    for (event_type, count) in &type_counts {
+        writeln!(out, "<tr><td>{}</td><td class=\"count\">{}</td></tr>", escape_html(event_type), count).unwrap();
+    }
+    out.push_str("</table>\n");
+
+    out.push_str("<h2>By repository</h2>\n<table>\n<tr><th>Repository</th><th>Count</th></tr>\n");
+    for (repo, count) in &repo_counts {
+        writeln!(out, "<tr><td>{}</td><td class=\"count\">{}</td></tr>", escape_html(repo), count).unwrap();
+    }
+    out.push_str("</table>\n");
+
+    let columns = linked_columns(context);
+    out.push_str("<h2>Events</h2>\n<table>\n<tr>");
+    for column in &columns {
+        let header = column.header();
+        write!(out, "<th>{}{}</th>", &header[..1], header[1..].to_lowercase()).unwrap();
+    }
+    out.push_str("</tr>\n");
+    for event in events {
+        out.push_str("<tr>");
+        for column in &columns {
+            match column {
+                Column::Repo => write!(out, "<td><a href=\"{}\">{}</a></td>",
+                    escape_html(&event.repo.html_url()), escape_html(&event.repo.name)),
+                Column::Visibility => {
+                    let visibility = visibility(event);
+                    write!(out, "<td class=\"{}\">{}</td>", visibility.to_lowercase(), visibility)
+                }
+                _ => write!(out, "<td>{}</td>", escape_html(&column.cell(event, context))),
+            }.unwrap();
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n</body>\n</html>\n");
+
+    out
+}
+
+/// Escape a TEXT value per RFC 5545 section 3.3.11
+fn escape_ics(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets without splitting UTF-8 sequences
+fn fold_ics_line(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > 75 {
+            out.push_str("\r\n ");
+            width = 1; // the leading space counts towards the next line
+        }
+        out.push(c);
+        width += len;
+    }
+    out.push_str("\r\n");
+    out
+}
+
+fn render_ics(events: &[Event]) -> String {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//wiwo//GitHub activity//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@wiwo", escape_ics(&event.dedup_key())));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", event.created_at.format("%Y%m%dT%H%M%SZ")));
+        lines.push(format!("SUMMARY:{}", escape_ics(&format!("{} in {}", event.formatted_type(), event.repo.name))));
+        lines.push(format!("DESCRIPTION:{}", escape_ics(&event.repo.html_url())));
+        lines.push(format!("URL:{}", event.repo.html_url()));
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|l| fold_ics_line(l)).collect()
+}
+
+/// `s` percent-encoded for a tag URI, leaving only unreserved characters as they are
+fn tag_escape(s: &str) -> String {
+    s.bytes()
+        .map(|b| if b.is_ascii_alphanumeric() || b"-._~".contains(&b) { char::from(b).to_string() } else { format!("%{:02X}", b) })
+        .collect()
+}
+
+/// An entry's id, the same every time the event is rendered: GitHub's id
+/// when it has one, else for git history the commit, else its time, type
+/// and repository
+fn atom_entry_id(event: &Event) -> String {
+    if let Some(id) = &event.id {
+        return format!("tag:github.com,2008:event/{}", tag_escape(id));
+    }
+    // Names are case-insensitive
+    let repo: Vec<String> = event.repo.name.to_lowercase().split('/').map(tag_escape).collect();
+    match event.payload.commits.as_slice() {
+        [Commit { sha: Some(sha), .. }] => format!("tag:wiwo,2025:commit/{}/{}", repo.join("/"), tag_escape(sha)),
+        _ => format!("tag:wiwo,2025:event/{}/{}/{}", repo.join("/"), tag_escape(&event.event_type), event.created_at.timestamp()),
+    }
+}
+
+fn render_atom(events: &[Event], context: &RenderContext) -> String {
+    let updated = events.iter()
+        .map(|e| e.created_at)
+        .max()
+        .unwrap_or_else(chrono::Utc::now);
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    out.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    writeln!(out, "  <title>GitHub activity for {} ({})</title>",
+        escape_html(context.username), escape_html(context.time_range)).unwrap();
+    let users: Vec<String> = context.username.split(", ").map(tag_escape).collect();
+    writeln!(out, "  <id>tag:wiwo,2025:{}</id>", users.join(",")).unwrap();
+    // Several users, joined with commas, have no one profile to link to
+    let link = if context.username.contains(", ") {
+        config::web_base()
+    } else {
+        format!("{}/{}", config::web_base(), context.username)
+    };
+    writeln!(out, "  <link href=\"{}\"/>", escape_html(&link)).unwrap();
+    writeln!(out, "  <updated>{}</updated>", updated.to_rfc3339()).unwrap();
+    writeln!(out, "  <author><name>{}</name></author>", escape_html(context.username)).unwrap();
+
+    for event in events {
+        let title = format!("{} in {}", event.formatted_type(), event.repo.name);
+
+        out.push_str("  <entry>\n");
+        writeln!(out, "    <id>{}</id>", atom_entry_id(event)).unwrap();
+        writeln!(out, "    <title>{}</title>", escape_html(&title)).unwrap();
+        writeln!(out, "    <updated>{}</updated>", event.created_at.to_rfc3339()).unwrap();
+        writeln!(out, "    <link href=\"{}\"/>", escape_html(&event.repo.html_url())).unwrap();
+        out.push_str("  </entry>\n");
+    }
+
+    out.push_str("</feed>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample_events;
+    use clap::ValueEnum;
+
+    fn context(columns: &[Column]) -> RenderContext<'_> {
+        RenderContext {
+            username: "octocat",
+            time_range: "last 30d",
+            columns,
+            time_format: TimeFormat::Absolute,
+            timezone: DisplayZone::Named(chrono_tz::UTC),
+            color: false,
+            max_width: None,
+            hyperlinks: false,
+            group_by: None,
+            summary: None,
+            since: "2025-02-01T00:00:00Z".parse().unwrap(),
+            until: None,
+            sparkline: false,
+            table_style: TableStyle::Ascii,
+            filtered: false,
+        }
+    }
+
+    #[test]
+    fn table_snapshots() {
+        let events = sample_events();
+        for style in [TableStyle::Ascii, TableStyle::Unicode, TableStyle::Compact] {
+            let context = RenderContext { table_style: style, ..context(Column::DEFAULT) };
+            let name = format!("table_{}", style.to_possible_value().unwrap().get_name());
+            insta::assert_snapshot!(name, render(OutputFormat::Table, &events, &context).unwrap());
+        }
+    }
+
+    #[test]
+    fn grouped_table_snapshot() {
+        let context = RenderContext { group_by: Some(GroupBy::Day), ..context(Column::DEFAULT) };
+        insta::assert_snapshot!(render(OutputFormat::Table, &sample_events(), &context).unwrap());
+    }
+
+    #[test]
+    fn narrow_table_snapshot() {
+        let columns = [Column::Timestamp, Column::Event, Column::Repo, Column::Url];
+        let context = RenderContext { max_width: Some(60), ..context(&columns) };
+        insta::assert_snapshot!(render(OutputFormat::Table, &sample_events(), &context).unwrap());
+    }
+
+    #[test]
+    fn empty_table_snapshot() {
+        let context = RenderContext { filtered: true, ..context(Column::DEFAULT) };
+        insta::assert_snapshot!(render(OutputFormat::Table, &[], &context).unwrap());
+    }
+
+    #[test]
+    fn markdown_snapshot() {
+        insta::assert_snapshot!(render(OutputFormat::Markdown, &sample_events(), &context(Column::DEFAULT)).unwrap());
+    }
+
+    #[test]
+    fn html_snapshot() {
+        insta::assert_snapshot!(render(OutputFormat::Html, &sample_events(), &context(Column::DEFAULT)).unwrap());
+    }
+
+    #[test]
+    fn team_columns_in_markdown_and_html() {
+        let columns = [Column::User, Column::Timestamp, Column::Actor, Column::Repo, Column::Details, Column::Url];
+        let mut events = sample_events();
+        events[0].payload.pull_request.as_mut().unwrap().title = "Fix | and <b>".to_string();
+        for (event, user) in events.iter_mut().zip(["alice", "bob", "alice"]) {
+            event.user = Some(user.to_string());
+        }
+        let context = context(&columns);
+        let markdown = render(OutputFormat::Markdown, &events, &context).unwrap();
+        insta::assert_snapshot!("team_markdown", markdown);
+        insta::assert_snapshot!("team_html", render(OutputFormat::Html, &events, &context).unwrap());
+        assert!(markdown.starts_with("| USER | TIMESTAMP | ACTOR | REPOSITORY | DETAILS |\n|---|---|---|---|---|\n"));
+    }
+
+    #[test]
+    fn atom_snapshot() {
+        insta::assert_snapshot!(render(OutputFormat::Atom, &sample_events(), &context(Column::DEFAULT)).unwrap());
+    }
+
+    #[test]
+    fn atom_links_a_team_to_the_host() {
+        let context = RenderContext { username: "alice, bob", ..context(Column::DEFAULT) };
+        let atom = render(OutputFormat::Atom, &sample_events(), &context).unwrap();
+        assert!(atom.contains("  <link href=\"https://github.com\"/>\n"), "{}", atom);
+    }
+
+    #[test]
+    fn atom_ids_are_valid_tag_uris() {
+        let context = RenderContext { username: "alice, bob", ..context(Column::DEFAULT) };
+        let mut events = sample_events();
+        events.extend(serde_json::from_value::<Vec<Event>>(serde_json::json!([
+            { "type": "GistEvent", "created_at": "2025-03-01T09:00:00Z", "repo": { "name": "gist:notes & todo.md" } }
+        ])).unwrap());
+        let atom = render(OutputFormat::Atom, &events, &context).unwrap();
+        let ids: Vec<&str> = atom.lines()
+            .filter_map(|line| line.trim().strip_prefix("<id>")?.strip_suffix("</id>"))
+            .collect();
+        assert_eq!(ids, [
+            "tag:wiwo,2025:alice,bob",
+            "tag:github.com,2008:event/2",
+            "tag:github.com,2008:event/1",
+            "tag:wiwo,2025:commit/octocat/old/def456",
+            "tag:wiwo,2025:event/gist%3Anotes%20%26%20todo.md/GistEvent/1740819600",
+        ]);
+    }
+
+    #[test]
+    fn ics_snapshot() {
+        let ics = render(OutputFormat::Ics, &sample_events(), &context(Column::DEFAULT)).unwrap();
+        // The stamp is when the calendar was made
+        let ics: Vec<&str> = ics.lines().filter(|line| !line.starts_with("DTSTAMP:")).collect();
+        insta::assert_snapshot!(ics.join("\n"));
+    }
+
+    #[test]
+    fn pad_to_width_counts_display_columns() {
+        assert_eq!(pad_to_width("abc", 5), "abc  ");
+        // Wide characters take two columns each
+        assert_eq!(pad_to_width("日本", 6), "日本  ");
+        // A combining accent takes none
+        assert_eq!(pad_to_width("cafe\u{301}", 6), "cafe\u{301}  ");
+        assert_eq!(pad_to_width("👍", 3), "👍 ");
+        assert_eq!(pad_to_width("👨‍👩‍👧", 3), "👨‍👩‍👧 ");
+    }
+
+    #[test]
+    fn pad_to_width_leaves_wider_text_alone() {
+        assert_eq!(pad_to_width("abcdef", 3), "abcdef");
+        assert_eq!(pad_to_width("日本語", 6), "日本語");
+        assert_eq!(pad_to_width("", 0), "");
+    }
+
+    #[test]
+    fn truncate_middle_keeps_both_ends() {
+        assert_eq!(truncate_middle("my-org/payments-service", 15), "my-org/…service");
+        assert_eq!(truncate_middle("abcdefgh", 5), "ab…gh");
+        assert_eq!(truncate_middle("abcdefgh", 4), "ab…h");
+    }
+
+    #[test]
+    fn truncate_middle_leaves_short_text_alone() {
+        assert_eq!(truncate_middle("abc", 3), "abc");
+        assert_eq!(truncate_middle("abc", 10), "abc");
+    }
+
+    #[test]
+    fn truncate_middle_edge_widths() {
+        assert_eq!(truncate_middle("abc", 0), "");
+        assert_eq!(truncate_middle("abc", 1), "…");
+    }
+
+    #[test]
+    fn truncate_middle_fits_wide_and_combining_characters() {
+        // A wide character that doesn't fit is left out rather than split
+        let truncated = truncate_middle("日本語テキスト", 6);
+        assert!(truncated.width() <= 6, "{:?} is wider than 6", truncated);
+        assert!(truncated.starts_with('日') && truncated.ends_with('ト') && truncated.contains('…'));
+        // Accents stay with their letters
+        assert_eq!(truncate_middle("e\u{301}e\u{301}e\u{301}e\u{301}e\u{301}", 3), "e\u{301}…e\u{301}");
+    }
+}
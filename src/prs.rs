@@ -0,0 +1,133 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::output::plain_table;
+use crate::search::{self, SearchItem};
+use crate::timezone::{self, DisplayZone};
+
+#[derive(clap::Args)]
+pub struct PrsArgs {
+    /// GitHub username (defaults to authenticated user if GH_TOKEN is set)
+    #[arg(short, long)]
+    user: Option<String>,
+    /// Time range the pull requests were created in (e.g., "30d", "1y", "last-month")
+    #[arg(short, long, default_value = "30d", value_parser = crate::parse_time_arg)]
+    time: String,
+    /// Only list pull requests in this state
+    #[arg(long, value_enum)]
+    state: Option<PrState>,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = PrsFormat::Table)]
+    format: PrsFormat,
+    /// Time zone for displaying dates: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PrsFormat {
+    Table,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PrState {
+    Open,
+    Merged,
+    /// Closed without being merged
+    Closed,
+}
+
+impl PrState {
+    /// Search qualifiers selecting this state
+    fn qualifier(self) -> &'static str {
+        match self {
+            PrState::Open => "is:open",
+            PrState::Merged => "is:merged",
+            PrState::Closed => "is:closed is:unmerged",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            PrState::Open => "open",
+            PrState::Merged => "merged",
+            PrState::Closed => "closed",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PullRequestSummary {
+    number: u64,
+    repo: String,
+    title: String,
+    state: PrState,
+    html_url: String,
+    created_at: DateTime<Utc>,
+    merged_at: Option<DateTime<Utc>>,
+}
+
+impl From<SearchItem> for PullRequestSummary {
+    fn from(item: SearchItem) -> Self {
+        let merged_at = item.pull_request.as_ref().and_then(|pr| pr.merged_at);
+        let state = match (item.state.as_str(), merged_at) {
+            (_, Some(_)) => PrState::Merged,
+            ("open", None) => PrState::Open,
+            _ => PrState::Closed,
+        };
+        PullRequestSummary {
+            number: item.number,
+            repo: item.repo_name().to_string(),
+            title: item.title,
+            state,
+            html_url: item.html_url,
+            created_at: item.created_at,
+            merged_at,
+        }
+    }
+}
+
+fn render_table(prs: &[PullRequestSummary], timezone: DisplayZone) -> String {
+    if prs.is_empty() {
+        return "No pull requests found.\n".to_string();
+    }
+
+    let rows: Vec<Vec<String>> = prs.iter()
+        .map(|pr| vec![
+            format!("#{}", pr.number),
+            pr.repo.clone(),
+            pr.state.label().to_string(),
+            timezone.format(pr.created_at, "%Y-%m-%d"),
+            pr.merged_at.map(|t| timezone.format(t, "%Y-%m-%d")).unwrap_or_default(),
+            pr.title.clone(),
+        ])
+        .collect();
+    plain_table(&["NUMBER", "REPOSITORY", "STATE", "CREATED", "MERGED", "TITLE"], &rows)
+}
+
+pub async fn run(args: &PrsArgs) -> Result<()> {
+    let now = Utc::now();
+    let (since, until) = crate::parse_time_range(&args.time, now, args.timezone)?;
+
+    let (client, headers) = crate::setup_github_client()?;
+    let username = crate::resolve_username(&client, &headers, args.user.as_deref()).await?;
+
+    let mut query = format!("type:pr author:{} {}", username, search::range_qualifier("created", since, until));
+    if let Some(state) = args.state {
+        query = format!("{} {}", query, state.qualifier());
+    }
+
+    let prs: Vec<PullRequestSummary> = search::search_issues(&client, &headers, &query).await?
+        .into_iter()
+        .map(PullRequestSummary::from)
+        .collect();
+    match args.format {
+        PrsFormat::Table => print!("{}", render_table(&prs, args.timezone)),
+        PrsFormat::Json => println!("{}", serde_json::to_string_pretty(&prs)?),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,259 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::net::SendRetrying;
+use crate::output::plain_table;
+use crate::timezone::DisplayZone;
+
+/// The latest quota GitHub reported for each bucket, from response headers
+static LAST_SEEN: Mutex<BTreeMap<String, Quota>> = Mutex::new(BTreeMap::new());
+
+/// Whether a run has been warned that the quota won't last, which it only needs once
+static WARNED_SHORT: AtomicBool = AtomicBool::new(false);
+
+#[derive(clap::Args)]
+pub struct RateLimitArgs {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = RateLimitFormat::Table)]
+    format: RateLimitFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum RateLimitFormat {
+    Table,
+    Json,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Quota {
+    limit: u64,
+    remaining: u64,
+    /// Unix time the bucket refills
+    reset: i64,
+}
+
+impl Quota {
+    fn reset_time(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.reset, 0).unwrap_or_default()
+    }
+
+    /// e.g. "4990/5000 remaining, resets 14:05:00 (in 42 minutes)"
+    fn describe(&self) -> String {
+        let minutes = (self.reset_time() - Utc::now()).num_minutes().max(0);
+        format!("{}/{} remaining, resets {} (in {} {})",
+            self.remaining, self.limit,
+            DisplayZone::Local.format(self.reset_time(), "%H:%M:%S"),
+            minutes, if minutes == 1 { "minute" } else { "minutes" })
+    }
+}
+
+/// Remember the quota from a response's `x-ratelimit-*` headers, for
+/// `--show-rate-limit`
+pub fn record(headers: &HeaderMap) {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let number = |name: &str| header(name).and_then(|v| v.parse::<i64>().ok());
+    if let (Some(limit), Some(remaining), Some(reset)) =
+        (number("x-ratelimit-limit"), number("x-ratelimit-remaining"), number("x-ratelimit-reset"))
+    {
+        let resource = header("x-ratelimit-resource").unwrap_or("core").to_string();
+        let quota = Quota { limit: limit.max(0) as u64, remaining: remaining.max(0) as u64, reset };
+        LAST_SEEN.lock().unwrap().insert(resource, quota);
+    }
+}
+
+/// Print the quotas seen during the run to stderr
+pub fn report_last_seen() {
+    let last_seen = LAST_SEEN.lock().unwrap();
+    if last_seen.is_empty() {
+        eprintln!("Rate limit: no GitHub API responses were received");
+    }
+    for (resource, quota) in last_seen.iter() {
+        eprintln!("Rate limit ({}): {}", resource, quota.describe());
+    }
+}
+
+/// What a run is about to fetch, for estimating the requests it needs
+#[derive(Debug, Default)]
+pub struct PlannedWork {
+    /// Pages of events, over all users and endpoints
+    pub event_pages: usize,
+    /// Repositories whose details aren't cached yet
+    pub repositories: usize,
+    /// Whether repositories are looked up over GraphQL, which takes a hundred
+    /// per request, rather than one REST request each
+    pub graphql: bool,
+}
+
+/// Requests a run needs from the core and GraphQL buckets
+#[derive(Debug, Default, PartialEq)]
+pub struct Estimate {
+    pub core: u64,
+    pub graphql: u64,
+}
+
+/// Requests `work` takes: a request per page of events, plus a request per
+/// repository over REST or one per batch over GraphQL
+pub fn estimate(work: &PlannedWork) -> Estimate {
+    let pages = work.event_pages as u64;
+    if work.graphql {
+        Estimate { core: pages, graphql: work.repositories.div_ceil(crate::graphql::BATCH_SIZE) as u64 }
+    } else {
+        Estimate { core: pages + work.repositories as u64, graphql: 0 }
+    }
+}
+
+/// The first bucket whose quota in `last_seen` won't cover `estimate`, with
+/// the requests needed from it; buckets not seen yet are taken to be fine
+fn shortfall<'a>(estimate: &Estimate, last_seen: &'a BTreeMap<String, Quota>) -> Option<(&'static str, u64, &'a Quota)> {
+    [("core", estimate.core), ("graphql", estimate.graphql)].into_iter()
+        .find_map(|(resource, needed)| {
+            last_seen.get(resource).filter(|quota| needed > quota.remaining).map(|quota| (resource, needed, quota))
+        })
+}
+
+/// Warn, once per run, if the last seen quota of a bucket won't cover `estimate`
+pub fn warn_if_short(estimate: &Estimate) {
+    let last_seen = LAST_SEEN.lock().unwrap();
+    if let Some((resource, needed, quota)) = shortfall(estimate, &last_seen)
+        && !WARNED_SHORT.swap(true, Ordering::Relaxed)
+    {
+        warn!("Warning: This run needs about {} {} API requests, but the quota has {}. \
+            Results may be incomplete; narrow the time range, skip repository lookups with \
+            `events --no-visibility`, or wait for the reset.",
+            needed, resource, quota.describe());
+    }
+}
+
+async fn fetch_quotas(client: &reqwest::Client, headers: &HeaderMap) -> Result<PreflightResources> {
+    let response = client
+        .get(format!("{}/rate_limit", crate::config::api_base()))
+        .headers(headers.clone())
+        .send_retrying()
+        .await
+        .context("Failed to fetch the rate limit")?;
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to fetch the rate limit ({})", response.status());
+    }
+    Ok(response.json::<PreflightResponse>().await
+        .context("Failed to parse the rate limit response")?
+        .resources)
+}
+
+/// Check the quota before a run that needs `estimate`, warning if it won't
+/// last. The quotas also seed what later checks go by.
+pub async fn preflight(client: &reqwest::Client, headers: &HeaderMap, estimate: &Estimate) {
+    match fetch_quotas(client, headers).await {
+        Ok(resources) => {
+            let mut last_seen = LAST_SEEN.lock().unwrap();
+            last_seen.insert("core".to_string(), resources.core);
+            if let Some(graphql) = resources.graphql {
+                last_seen.insert("graphql".to_string(), graphql);
+            }
+        }
+        // Only a warning would be lost; the run itself can go ahead
+        Err(e) => {
+            debug!("Skipping the rate limit check: {:#}", e);
+            return;
+        }
+    }
+    warn_if_short(estimate);
+}
+
+#[derive(Debug, Deserialize)]
+struct PreflightResponse {
+    resources: PreflightResources,
+}
+
+/// The buckets a run draws on; anonymous requests get no GraphQL quota
+#[derive(Debug, Deserialize)]
+struct PreflightResources {
+    core: Quota,
+    graphql: Option<Quota>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RateLimitResponse {
+    resources: RateLimitResources,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RateLimitResources {
+    core: Quota,
+    search: Quota,
+}
+
+pub async fn run(args: &RateLimitArgs) -> Result<()> {
+    let (client, headers) = crate::setup_github_client()?;
+    // Checking the rate limit doesn't count against it
+    let response = client
+        .get(format!("{}/rate_limit", crate::config::api_base()))
+        .headers(headers)
+        .send_retrying()
+        .await
+        .context("Failed to fetch the rate limit")?;
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to fetch the rate limit ({})", response.status());
+    }
+    let resources = response.json::<RateLimitResponse>().await
+        .context("Failed to parse the rate limit response")?
+        .resources;
+
+    match args.format {
+        RateLimitFormat::Table => {
+            let rows: Vec<Vec<String>> = [("core", &resources.core), ("search", &resources.search)].iter()
+                .map(|(name, quota)| vec![
+                    name.to_string(),
+                    quota.remaining.to_string(),
+                    quota.limit.to_string(),
+                    DisplayZone::Local.format(quota.reset_time(), "%Y-%m-%d %H:%M:%S %Z"),
+                ])
+                .collect();
+            print!("{}", plain_table(&["BUCKET", "REMAINING", "LIMIT", "RESETS"], &rows));
+        }
+        RateLimitFormat::Json => println!("{}", serde_json::to_string_pretty(&resources)?),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphql::BATCH_SIZE;
+
+    #[test]
+    fn estimate_counts_a_request_per_repository_over_rest() {
+        let work = PlannedWork { event_pages: 6, repositories: 250, graphql: false };
+        assert_eq!(estimate(&work), Estimate { core: 256, graphql: 0 });
+        assert_eq!(estimate(&PlannedWork::default()), Estimate::default());
+    }
+
+    #[test]
+    fn estimate_counts_a_request_per_batch_over_graphql() {
+        let work = |repositories| PlannedWork { event_pages: 6, repositories, graphql: true };
+        assert_eq!(estimate(&work(0)), Estimate { core: 6, graphql: 0 });
+        assert_eq!(estimate(&work(1)), Estimate { core: 6, graphql: 1 });
+        assert_eq!(estimate(&work(BATCH_SIZE)), Estimate { core: 6, graphql: 1 });
+        assert_eq!(estimate(&work(BATCH_SIZE + 1)), Estimate { core: 6, graphql: 2 });
+    }
+
+    #[test]
+    fn short_once_the_estimate_exceeds_what_remains() {
+        let quota = |remaining| Quota { limit: 5000, remaining, reset: 0 };
+        let last_seen = BTreeMap::from([("core".to_string(), quota(100))]);
+        assert!(shortfall(&Estimate { core: 100, graphql: 0 }, &last_seen).is_none());
+        let (resource, needed, _) = shortfall(&Estimate { core: 101, graphql: 0 }, &last_seen).unwrap();
+        assert_eq!((resource, needed), ("core", 101));
+        // No GraphQL quota seen, e.g. before the preflight check
+        assert!(shortfall(&Estimate { core: 1, graphql: 1000 }, &last_seen).is_none());
+
+        let last_seen = BTreeMap::from([("core".to_string(), quota(100)), ("graphql".to_string(), quota(0))]);
+        let (resource, needed, _) = shortfall(&Estimate { core: 1, graphql: 1 }, &last_seen).unwrap();
+        assert_eq!((resource, needed), ("graphql", 1));
+    }
+}
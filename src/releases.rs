@@ -0,0 +1,179 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+
+use crate::net::SendRetrying;
+use crate::output::plain_table;
+use crate::timezone::{self, DisplayZone};
+
+#[derive(clap::Args)]
+pub struct ReleasesArgs {
+    /// GitHub username (defaults to authenticated user if GH_TOKEN is set)
+    #[arg(short, long)]
+    user: Option<String>,
+    /// Time range the releases were published in (e.g., "90d", "1y", "last-month")
+    #[arg(short, long, default_value = "90d", value_parser = crate::parse_time_arg)]
+    time: String,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = ReleasesFormat::Table)]
+    format: ReleasesFormat,
+    /// List releases of this organization's repositories instead of the user's (repeatable)
+    #[arg(long = "org")]
+    orgs: Vec<String>,
+    /// Time zone for displaying dates: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReleasesFormat {
+    Table,
+    Json,
+}
+
+/// One release from `repos/{owner}/{repo}/releases`
+#[derive(Debug, Deserialize)]
+struct ApiRelease {
+    tag_name: String,
+    name: Option<String>,
+    html_url: String,
+    draft: bool,
+    prerelease: bool,
+    created_at: DateTime<Utc>,
+    /// `None` for drafts
+    published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Release {
+    repo: String,
+    tag: String,
+    name: String,
+    html_url: String,
+    draft: bool,
+    prerelease: bool,
+    published_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl Release {
+    /// When the release went out, or for drafts when they were started
+    fn date(&self) -> DateTime<Utc> {
+        self.published_at.unwrap_or(self.created_at)
+    }
+
+    fn status(&self) -> &'static str {
+        match (self.draft, self.prerelease) {
+            (true, _) => "draft",
+            (false, true) => "prerelease",
+            (false, false) => "release",
+        }
+    }
+}
+
+/// Releases of `repo` published (or for drafts, created) in the range. The
+/// API lists newest first, so paging stops at the first page reaching past `since`.
+async fn fetch_releases(client: &reqwest::Client, headers: &HeaderMap, repo: &str, since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Vec<Release>> {
+    let mut releases = Vec::new();
+    let mut page = 1;
+    loop {
+        let url = format!("{}/repos/{}/releases?per_page=100&page={}", crate::config::api_base(), repo, page);
+        let response = client
+            .get(&url)
+            .headers(headers.clone())
+            .send_retrying()
+            .await
+            .context(format!("Failed to fetch releases for {}", repo))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to fetch releases for {} ({})", repo, response.status());
+        }
+        let page_releases: Vec<ApiRelease> = response.json().await
+            .context("Failed to parse releases response")?;
+        let done = page_releases.is_empty() || page_releases.iter().any(|r| r.created_at < since);
+
+        releases.extend(page_releases.into_iter()
+            .map(|r| Release {
+                repo: repo.to_string(),
+                name: r.name.filter(|name| !name.is_empty()).unwrap_or_else(|| r.tag_name.clone()),
+                tag: r.tag_name,
+                html_url: r.html_url,
+                draft: r.draft,
+                prerelease: r.prerelease,
+                published_at: r.published_at,
+                created_at: r.created_at,
+            })
+            .filter(|r| r.date() >= since && r.date() <= until));
+        if done {
+            break;
+        }
+        page += 1;
+    }
+    Ok(releases)
+}
+
+fn render_table(releases: &[Release], timezone: DisplayZone) -> String {
+    if releases.is_empty() {
+        return "No releases found.\n".to_string();
+    }
+
+    let rows: Vec<Vec<String>> = releases.iter()
+        .map(|r| vec![
+            r.tag.clone(),
+            r.repo.clone(),
+            r.name.clone(),
+            r.published_at.map(|t| timezone.format(t, "%Y-%m-%d")).unwrap_or_else(|| "-".to_string()),
+            r.status().to_string(),
+            r.html_url.clone(),
+        ])
+        .collect();
+    plain_table(&["TAG", "REPOSITORY", "NAME", "PUBLISHED", "STATUS", "URL"], &rows)
+}
+
+pub async fn run(args: &ReleasesArgs, options: &crate::RunOptions) -> Result<()> {
+    let now = Utc::now();
+    let (since, until) = crate::parse_time_range(&args.time, now, args.timezone)?;
+    let until = until.unwrap_or(now);
+
+    let (client, headers) = crate::setup_github_client()?;
+
+    // Forks are left out: their releases are rarely the owner's own
+    let mut repos: Vec<String> = Vec::new();
+    if args.orgs.is_empty() {
+        let username = crate::resolve_username(&client, &headers, args.user.as_deref()).await?;
+        repos.extend(crate::fetch_user_repositories(&client, &headers, &username, false).await?
+            .into_iter()
+            .map(|r| r.name));
+    }
+    for org in &args.orgs {
+        match crate::fetch_org_repositories(&client, &headers, org, false).await {
+            Ok(org_repos) => repos.extend(org_repos.into_iter().map(|r| r.name)),
+            Err(e) => warn!("Warning: Failed to fetch repositories for organization {}: {}", org, e),
+        }
+    }
+
+    let (client, headers) = (&client, &headers);
+    let mut releases: Vec<Release> = futures::stream::iter(repos)
+        .map(|repo| async move {
+            match fetch_releases(client, headers, &repo, since, until).await {
+                Ok(releases) => releases,
+                Err(e) => {
+                    warn!("Warning: {}", e);
+                    Vec::new()
+                }
+            }
+        })
+        .buffer_unordered(options.lookup_concurrency)
+        .flat_map(futures::stream::iter)
+        .collect()
+        .await;
+    releases.sort_by(|a, b| b.date().cmp(&a.date()).then_with(|| a.repo.cmp(&b.repo)));
+
+    match args.format {
+        ReleasesFormat::Table => print!("{}", render_table(&releases, args.timezone)),
+        ReleasesFormat::Json => println!("{}", serde_json::to_string_pretty(&releases)?),
+    }
+
+    Ok(())
+}
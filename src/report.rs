@@ -0,0 +1,159 @@
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, Utc, Weekday};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use crate::timezone::{self, DisplayZone};
+use crate::Event;
+
+#[derive(clap::Args)]
+pub struct ReportArgs {
+    /// GitHub username (defaults to authenticated user if GH_TOKEN is set)
+    #[arg(short, long)]
+    user: Option<String>,
+    /// Report on the previous complete week or month
+    #[arg(long, value_enum, default_value_t = Period::Week)]
+    period: Period,
+    /// First day of the week, e.g. "monday" or "sun"
+    #[arg(long, value_parser = parse_weekday, default_value = "monday")]
+    week_start: Weekday,
+    /// Write the report to a file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Time zone for deciding where days start: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Period {
+    Week,
+    Month,
+}
+
+fn parse_weekday(s: &str) -> std::result::Result<Weekday, String> {
+    s.parse().map_err(|_| format!("invalid weekday '{}'; use a name like 'monday' or 'sun'", s))
+}
+
+/// First day of the last complete period before `today`, and the first day after it
+fn previous_period(today: NaiveDate, period: Period, week_start: Weekday) -> (NaiveDate, NaiveDate) {
+    match period {
+        Period::Week => {
+            let into_week = (7 + today.weekday().num_days_from_monday() - week_start.num_days_from_monday()) % 7;
+            let end = today - Duration::days(into_week as i64);
+            (end - Duration::days(7), end)
+        }
+        Period::Month => {
+            let end = today.with_day(1).unwrap();
+            let start = (end - Duration::days(1)).with_day(1).unwrap();
+            (start, end)
+        }
+    }
+}
+
+/// A line for a pull request or issue that was opened, merged or closed
+fn notable(event: &Event) -> Option<String> {
+    let action = event.payload.action.as_deref()?;
+    let (noun, verb) = match (event.event_type.as_str(), &event.payload.pull_request) {
+        ("PullRequestEvent", Some(pr)) => match action {
+            "closed" if pr.merged == Some(true) => ("PR", "Merged"),
+            "opened" => ("PR", "Opened"),
+            "closed" => ("PR", "Closed"),
+            _ => return None,
+        },
+        ("IssuesEvent", _) => match action {
+            "opened" => ("issue", "Opened"),
+            "closed" => ("issue", "Closed"),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let details = event.details();
+    let details = if details.is_empty() { String::new() } else { format!(": {}", details) };
+    Some(format!("- {} {} in {}{}", verb, noun, event.repo.name, details))
+}
+
+fn render(events: &[Event], username: &str, period: Period, start: NaiveDate, end: NaiveDate, timezone: DisplayZone) -> String {
+    let mut out = String::new();
+    let last = end - Duration::days(1);
+    let title = match period {
+        Period::Week => "Weekly report",
+        Period::Month => "Monthly report",
+    };
+    writeln!(out, "# {} for {}: {} to {}\n", title, username, start, last).unwrap();
+
+    let repos: HashSet<&str> = events.iter().map(|e| e.repo.name.as_str()).collect();
+    let mut per_day: BTreeMap<NaiveDate, usize> = start.iter_days().take_while(|d| *d < end).map(|d| (d, 0)).collect();
+    for event in events {
+        *per_day.entry(timezone.naive_local(event.created_at).date()).or_default() += 1;
+    }
+
+    writeln!(out, "## Highlights\n").unwrap();
+    writeln!(out, "- {} events across {} repositories", events.len(), repos.len()).unwrap();
+    let active_days = per_day.values().filter(|&&count| count > 0).count();
+    writeln!(out, "- Active on {} of {} days", active_days, per_day.len()).unwrap();
+    // The earliest of equally busy days
+    if let Some((day, count)) = per_day.iter().rev().max_by_key(|(_, count)| **count).filter(|(_, count)| **count > 0) {
+        writeln!(out, "- Busiest day: {} ({} events)", day.format("%A %Y-%m-%d"), count).unwrap();
+    }
+
+    writeln!(out, "\n## By day\n").unwrap();
+    writeln!(out, "| Day | Events |").unwrap();
+    writeln!(out, "|---|---|").unwrap();
+    for (day, count) in &per_day {
+        writeln!(out, "| {} | {} |", day.format("%a %Y-%m-%d"), count).unwrap();
+    }
+
+    let notable: Vec<String> = events.iter().rev().filter_map(notable).collect();
+    if !notable.is_empty() {
+        writeln!(out, "\n## Pull requests and issues\n").unwrap();
+        for line in notable {
+            writeln!(out, "{}", line).unwrap();
+        }
+    }
+
+    if !events.is_empty() {
+        writeln!(out, "\n## By repository\n").unwrap();
+        let mut by_repo: BTreeMap<&str, (String, BTreeMap<String, usize>)> = BTreeMap::new();
+        for event in events {
+            let (_, counts) = by_repo.entry(&event.repo.name)
+                .or_insert_with(|| (event.repo.html_url(), BTreeMap::new()));
+            let weight = if event.formatted_type() == "Push" { event.payload.size.unwrap_or(1) } else { 1 };
+            *counts.entry(event.formatted_type()).or_default() += weight;
+        }
+        for (name, (url, counts)) in &by_repo {
+            writeln!(out, "### [{}]({})\n", name, url).unwrap();
+            for (formatted_type, count) in counts {
+                writeln!(out, "- {}", crate::standup::describe(formatted_type, *count)).unwrap();
+            }
+            writeln!(out).unwrap();
+        }
+    }
+
+    out
+}
+
+pub async fn run(args: &ReportArgs) -> Result<()> {
+    let today = args.timezone.naive_local(Utc::now()).date();
+    let (start, end) = previous_period(today, args.period, args.week_start);
+    let since = args.timezone.to_utc(start.and_hms_opt(0, 0, 0).unwrap());
+    let until = args.timezone.to_utc(end.and_hms_opt(0, 0, 0).unwrap());
+
+    let (client, headers) = crate::setup_github_client()?;
+    let username = crate::resolve_username(&client, &headers, args.user.as_deref()).await?;
+
+    let mut events = crate::fetch_events_in_range(&client, &headers, &username, since..until).await?;
+    events.sort_by_key(|e| std::cmp::Reverse(e.created_at));
+
+    let report = render(&events, &username, args.period, start, end, args.timezone);
+    match &args.output {
+        Some(path) => {
+            crate::output::write_atomic(path, &report)?;
+            println!("Wrote {}", path.display());
+        }
+        None => print!("{}", report),
+    }
+
+    Ok(())
+}
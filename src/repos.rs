@@ -0,0 +1,176 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use unicode_width::UnicodeWidthStr;
+
+use crate::timezone::{self, DisplayZone};
+use crate::Event;
+
+#[derive(clap::Args)]
+pub struct ReposArgs {
+    /// GitHub username (defaults to authenticated user if GH_TOKEN is set)
+    #[arg(short, long)]
+    user: Option<String>,
+    /// Time range to cover (e.g., "90d", "2w", "this-week"); limited to what the Events API returns
+    #[arg(short, long, default_value = "90d", value_parser = crate::parse_time_arg)]
+    time: String,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = ReposFormat::Table)]
+    format: ReposFormat,
+    /// Time zone for displaying timestamps: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReposFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+/// Activity in one repository over the range
+#[derive(Debug, Serialize)]
+struct RepoStats {
+    repo: String,
+    /// `None` when it couldn't be determined
+    private: Option<bool>,
+    events: usize,
+    first_activity: DateTime<Utc>,
+    last_activity: DateTime<Utc>,
+    /// Most frequent event type, the first alphabetically on a tie
+    dominant_type: String,
+}
+
+/// Per-repository stats, most events first with ties broken by name
+fn stats(events: &[Event]) -> Vec<RepoStats> {
+    let mut by_repo: HashMap<&str, Vec<&Event>> = HashMap::new();
+    for event in events {
+        by_repo.entry(&event.repo.name).or_default().push(event);
+    }
+
+    let mut stats: Vec<RepoStats> = by_repo.into_iter()
+        .map(|(repo, events)| {
+            let mut types: HashMap<String, usize> = HashMap::new();
+            for event in &events {
+                *types.entry(event.formatted_type()).or_default() += 1;
+            }
+            let dominant_type = types.into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+                .map(|(event_type, _)| event_type)
+                .unwrap_or_default();
+            RepoStats {
+                repo: repo.to_string(),
+                private: events[0].repo.private,
+                events: events.len(),
+                first_activity: events.iter().map(|e| e.created_at).min().unwrap(),
+                last_activity: events.iter().map(|e| e.created_at).max().unwrap(),
+                dominant_type,
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.events.cmp(&a.events).then_with(|| a.repo.cmp(&b.repo)));
+    stats
+}
+
+fn visibility(private: Option<bool>) -> &'static str {
+    match private {
+        Some(true) => "Private",
+        Some(false) => "Public",
+        None => "Unknown",
+    }
+}
+
+const HEADERS: [&str; 6] = ["REPOSITORY", "VISIBILITY", "EVENTS", "FIRST ACTIVITY", "LAST ACTIVITY", "TYPE"];
+
+fn render_table(stats: &[RepoStats], timezone: DisplayZone) -> String {
+    if stats.is_empty() {
+        return "No events found.\n".to_string();
+    }
+
+    let rows: Vec<[String; 6]> = stats.iter()
+        .map(|s| [
+            s.repo.clone(),
+            visibility(s.private).to_string(),
+            s.events.to_string(),
+            timezone.format(s.first_activity, "%Y-%m-%d %H:%M"),
+            timezone.format(s.last_activity, "%Y-%m-%d %H:%M"),
+            s.dominant_type.clone(),
+        ])
+        .collect();
+    let widths: Vec<usize> = (0..HEADERS.len())
+        .map(|i| rows.iter().map(|row| row[i].width()).chain([HEADERS[i].len()]).max().unwrap_or(0))
+        .collect();
+
+    let mut out = String::new();
+    let mut write_row = |cells: &[&str]| {
+        let line: Vec<String> = cells.iter().zip(&widths)
+            .enumerate()
+            // Right-align the count, and leave the last column unpadded
+            .map(|(i, (cell, &width))| match i {
+                2 => format!("{:>width$}", cell, width = width),
+                5 => cell.to_string(),
+                _ => crate::output::pad_to_width(cell, width),
+            })
+            .collect();
+        writeln!(out, "{}", line.join("  ")).unwrap();
+    };
+    write_row(&HEADERS);
+    for row in &rows {
+        write_row(&row.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+
+    out
+}
+
+/// Quote a CSV field per RFC 4180 when it contains a separator, quote or newline
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn render_csv(stats: &[RepoStats]) -> String {
+    let mut out = String::from("repo,visibility,events,first_activity,last_activity,dominant_type\n");
+    for s in stats {
+        writeln!(out, "{},{},{},{},{},{}",
+            csv_field(&s.repo),
+            visibility(s.private).to_lowercase(),
+            s.events,
+            s.first_activity.to_rfc3339(),
+            s.last_activity.to_rfc3339(),
+            csv_field(&s.dominant_type),
+        ).unwrap();
+    }
+    out
+}
+
+pub async fn run(args: &ReposArgs, options: &crate::RunOptions) -> Result<()> {
+    let now = Utc::now();
+    let (since, until) = crate::parse_time_range(&args.time, now, args.timezone)?;
+    let until = until.unwrap_or(now);
+
+    let (client, headers) = crate::setup_github_client()?;
+    let username = crate::resolve_username(&client, &headers, args.user.as_deref()).await?;
+
+    let mut events = crate::fetch_events_in_range(&client, &headers, &username, since..=until).await?;
+
+    let cache = Arc::new(RwLock::new(crate::cache::load_repositories()));
+    crate::resolve_repositories(&client, &headers, &cache, &mut events, options.lookup_concurrency).await?;
+
+    let stats = stats(&events);
+    let rendered = match args.format {
+        ReposFormat::Table => render_table(&stats, args.timezone),
+        ReposFormat::Json => format!("{}\n", serde_json::to_string_pretty(&stats)?),
+        ReposFormat::Csv => render_csv(&stats),
+    };
+    print!("{}", rendered);
+
+    Ok(())
+}
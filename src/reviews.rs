@@ -0,0 +1,159 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::net::SendRetrying;
+use crate::output::plain_table;
+use crate::search::{self, SearchItem};
+use crate::timezone::{self, DisplayZone};
+
+#[derive(clap::Args)]
+pub struct ReviewsArgs {
+    /// GitHub username (defaults to authenticated user if GH_TOKEN is set)
+    #[arg(short, long)]
+    user: Option<String>,
+    /// Time range the reviewed pull requests were updated in (e.g., "30d", "last-month")
+    #[arg(short, long, default_value = "30d", value_parser = crate::parse_time_arg)]
+    time: String,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = ReviewsFormat::Table)]
+    format: ReviewsFormat,
+    /// Time zone for displaying dates: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReviewsFormat {
+    Table,
+    Json,
+}
+
+/// One review from `pulls/{number}/reviews`
+#[derive(Debug, Deserialize)]
+struct Review {
+    user: Option<search::SearchUser>,
+    /// APPROVED, CHANGES_REQUESTED, COMMENTED, DISMISSED or PENDING
+    state: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ReviewedPullRequest {
+    repo: String,
+    number: u64,
+    title: String,
+    author: String,
+    html_url: String,
+    updated_at: DateTime<Utc>,
+    /// The user's latest review verdict, e.g. "APPROVED"; `None` if the
+    /// reviews couldn't be fetched
+    review_state: Option<String>,
+}
+
+/// The user's latest decisive review state on a pull request: an approval or
+/// change request outranks later plain comments
+async fn fetch_review_state(client: &reqwest::Client, headers: &HeaderMap, repo: &str, number: u64, username: &str) -> Result<Option<String>> {
+    let mut reviews: Vec<Review> = Vec::new();
+    let mut page = 1;
+    loop {
+        let url = format!("{}/repos/{}/pulls/{}/reviews?per_page=100&page={}", crate::config::api_base(), repo, number, page);
+        let response = client
+            .get(&url)
+            .headers(headers.clone())
+            .send_retrying()
+            .await
+            .context(format!("Failed to fetch reviews for {}#{}", repo, number))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to fetch reviews for {}#{} ({})", repo, number, response.status());
+        }
+        let page_reviews: Vec<Review> = response.json().await
+            .context("Failed to parse reviews response")?;
+        if page_reviews.is_empty() {
+            break;
+        }
+        reviews.extend(page_reviews);
+        page += 1;
+    }
+
+    let mine: Vec<&str> = reviews.iter()
+        .filter(|r| r.user.as_ref().is_some_and(|u| u.login.eq_ignore_ascii_case(username)))
+        .map(|r| r.state.as_str())
+        .collect();
+    let state = mine.iter().rev()
+        .find(|state| !matches!(**state, "COMMENTED" | "PENDING"))
+        .or(mine.last())
+        .map(|state| state.to_string());
+    Ok(state)
+}
+
+fn render_table(reviews: &[ReviewedPullRequest], timezone: DisplayZone) -> String {
+    if reviews.is_empty() {
+        return "No reviewed pull requests found.\n".to_string();
+    }
+
+    let rows: Vec<Vec<String>> = reviews.iter()
+        .map(|r| vec![
+            r.repo.clone(),
+            format!("#{}", r.number),
+            r.author.clone(),
+            r.review_state.as_deref().map(|s| s.to_lowercase().replace('_', " ")).unwrap_or_else(|| "unknown".to_string()),
+            timezone.format(r.updated_at, "%Y-%m-%d"),
+            r.title.clone(),
+        ])
+        .collect();
+    let mut out = plain_table(&["REPOSITORY", "NUMBER", "AUTHOR", "REVIEW", "UPDATED", "TITLE"], &rows);
+
+    let repos: HashSet<&str> = reviews.iter().map(|r| r.repo.as_str()).collect();
+    let prs = if reviews.len() == 1 { "pull request" } else { "pull requests" };
+    let repo_noun = if repos.len() == 1 { "repository" } else { "repositories" };
+    writeln!(out, "\nReviewed {} {} across {} {}", reviews.len(), prs, repos.len(), repo_noun).unwrap();
+    out
+}
+
+pub async fn run(args: &ReviewsArgs, options: &crate::RunOptions) -> Result<()> {
+    let now = Utc::now();
+    let (since, until) = crate::parse_time_range(&args.time, now, args.timezone)?;
+
+    let (client, headers) = crate::setup_github_client()?;
+    let username = crate::resolve_username(&client, &headers, args.user.as_deref()).await?;
+
+    // Search can't filter on when a review was left, so use when the PR was last updated
+    let query = format!("type:pr reviewed-by:{} -author:{} {}", username, username, search::range_qualifier("updated", since, until));
+    let items: Vec<SearchItem> = search::search_issues(&client, &headers, &query).await?;
+
+    let (client, headers, username) = (&client, &headers, username.as_str());
+    let reviews: Vec<ReviewedPullRequest> = futures::stream::iter(items)
+        .map(|item| async move {
+            let review_state = match fetch_review_state(client, headers, item.repo_name(), item.number, username).await {
+                Ok(state) => state,
+                Err(e) => {
+                    warn!("Warning: {}", e);
+                    None
+                }
+            };
+            ReviewedPullRequest {
+                repo: item.repo_name().to_string(),
+                number: item.number,
+                author: item.author().to_string(),
+                title: item.title,
+                html_url: item.html_url,
+                updated_at: item.updated_at,
+                review_state,
+            }
+        })
+        // Keep the search order, newest first
+        .buffered(options.lookup_concurrency)
+        .collect()
+        .await;
+
+    match args.format {
+        ReviewsFormat::Table => print!("{}", render_table(&reviews, args.timezone)),
+        ReviewsFormat::Json => println!("{}", serde_json::to_string_pretty(&reviews)?),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+
+use crate::net::SendRetrying;
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    total_count: usize,
+    items: Vec<SearchItem>,
+}
+
+/// An issue or pull request from `search/issues`
+#[derive(Debug, Deserialize)]
+pub struct SearchItem {
+    pub number: u64,
+    pub title: String,
+    /// "open" or "closed"
+    pub state: String,
+    pub html_url: String,
+    /// API URL of the repository, ending in `/repos/{owner}/{name}`
+    repository_url: String,
+    /// Author of the issue or pull request
+    pub user: Option<SearchUser>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Present only for pull requests
+    pub pull_request: Option<SearchPullRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchUser {
+    pub login: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchPullRequest {
+    pub merged_at: Option<DateTime<Utc>>,
+}
+
+impl SearchItem {
+    /// `owner/name` of the repository
+    pub fn repo_name(&self) -> &str {
+        self.repository_url.split("/repos/").nth(1).unwrap_or(&self.repository_url)
+    }
+
+    pub fn author(&self) -> &str {
+        self.user.as_ref().map_or("", |user| user.login.as_str())
+    }
+}
+
+/// A qualifier such as `created:` restricting `field` to the range, in the
+/// form the search API expects
+pub fn range_qualifier(field: &str, since: DateTime<Utc>, until: Option<DateTime<Utc>>) -> String {
+    let format = "%Y-%m-%dT%H:%M:%SZ";
+    match until {
+        Some(until) => format!("{}:{}..{}", field, since.format(format), until.format(format)),
+        None => format!("{}:>={}", field, since.format(format)),
+    }
+}
+
+/// The search API returns at most this many results per query
+const MAX_RESULTS: usize = 1000;
+
+/// Longest wait for the search rate limit (30 requests a minute with a token,
+/// 10 without) to reset before giving up
+const MAX_BACKOFF_SECS: u64 = 120;
+
+/// Fetch every page of `search/issues` results for `query`, newest first,
+/// waiting out the search API's own rate limit when it's hit
+pub async fn search_issues(client: &reqwest::Client, headers: &HeaderMap, query: &str) -> Result<Vec<SearchItem>> {
+    let mut results = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let response = client
+            .get(format!("{}/search/issues", crate::config::api_base()))
+            .headers(headers.clone())
+            .query(&[("q", query), ("sort", "created"), ("order", "desc"), ("per_page", "100"), ("page", &page.to_string())])
+            .send_retrying()
+            .await
+            .context("Failed to search GitHub")?;
+
+        crate::ratelimit::record(response.headers());
+        let status = response.status();
+        let header = |name: &str| response.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<i64>().ok());
+        let rate_limited = status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            || (status == reqwest::StatusCode::FORBIDDEN
+                && (header("x-ratelimit-remaining") == Some(0) || header("retry-after").is_some()));
+        if rate_limited {
+            // Prefer Retry-After (secondary limits), else wait for the window to reset
+            let wait = header("retry-after").or_else(|| {
+                header("x-ratelimit-reset").map(|reset| reset - Utc::now().timestamp())
+            });
+            match wait {
+                Some(wait) if wait.max(0) as u64 <= MAX_BACKOFF_SECS => {
+                    let wait = wait.max(0) as u64 + 1;
+                    warn!("Search rate limit reached. Waiting {} seconds...", wait);
+                    tokio::time::sleep(tokio::time::Duration::from_secs(wait)).await;
+                    continue;
+                }
+                _ => anyhow::bail!("Search rate limit reached ({}); try again later", status),
+            }
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Search failed ({}): {}", status, body.trim());
+        }
+
+        let response: SearchResponse = response.json().await
+            .context("Failed to parse search response")?;
+        let done = response.items.is_empty();
+        results.extend(response.items);
+
+        if done || results.len() >= response.total_count.min(MAX_RESULTS) {
+            if response.total_count > MAX_RESULTS {
+                warn!("Note: Only the {} most recent of {} search results are available; use a shorter --time", MAX_RESULTS, response.total_count);
+            }
+            break;
+        }
+        page += 1;
+    }
+
+    Ok(results)
+}
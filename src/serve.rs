@@ -0,0 +1,216 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio::time::{Duration, Instant};
+
+use crate::summary::{self, Summary};
+use crate::timezone::{self, DisplayZone};
+use crate::{Event, RepositoryCache};
+
+#[derive(clap::Args)]
+pub struct ServeArgs {
+    /// Port to listen on
+    #[arg(short, long, default_value_t = 8080)]
+    port: u16,
+    /// Address to listen on; use 0.0.0.0 to accept connections from other machines
+    #[arg(long, default_value = "127.0.0.1")]
+    bind: std::net::IpAddr,
+    /// Seconds to serve a user's events from memory before fetching them again
+    #[arg(long, default_value_t = 300)]
+    cache_ttl: u64,
+    /// Time zone for parsing time ranges and bucketing days: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+/// Events fetched for a (user, time range), and when
+struct CachedEvents {
+    fetched_at: Instant,
+    events: Arc<Vec<Event>>,
+}
+
+struct State {
+    client: reqwest::Client,
+    headers: HeaderMap,
+    repo_cache: RepositoryCache,
+    /// Login of the token's owner, the user when a request doesn't name one
+    default_user: Option<String>,
+    ttl: Duration,
+    timezone: DisplayZone,
+    lookup_concurrency: usize,
+    /// `?time=` is checked against it as `--time` is
+    max_range: crate::MaxRange,
+    events: HashMap<(String, String), CachedEvents>,
+}
+
+/// A failed request, with the status to answer it with
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        ApiError { status, message: message.into() }
+    }
+}
+
+fn json_response(status: StatusCode, body: String) -> Response<Body> {
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    response.headers_mut().insert(hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("application/json"));
+    response
+}
+
+/// The request's query string as key/value pairs, percent-decoded
+fn query_params(request: &Request<Body>) -> HashMap<String, String> {
+    let query = request.uri().query().unwrap_or("");
+    reqwest::Url::parse(&format!("http://localhost/?{}", query))
+        .map(|url| url.query_pairs().into_owned().collect())
+        .unwrap_or_default()
+}
+
+impl State {
+    /// The user's events in the range, newest first, from memory when fetched
+    /// within the TTL
+    async fn events(&mut self, user: &str, time: &str) -> Result<Arc<Vec<Event>>, ApiError> {
+        let now = Utc::now();
+        let (since, until) = crate::parse_time_range(time, now, self.timezone)
+            .and_then(|range| self.max_range.check(time).map(|()| range))
+            .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, format!("{:#}", e)))?;
+        let until = until.unwrap_or(now);
+
+        let key = (user.to_lowercase(), time.trim().to_string());
+        if let Some(cached) = self.events.get(&key)
+            && cached.fetched_at.elapsed() < self.ttl
+        {
+            return Ok(cached.events.clone());
+        }
+
+        // A GitHub App's token is replaced while the server runs
+        let headers = crate::current_headers(&self.headers)
+            .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        // Only --strict reads them, at exit, which a server never reaches
+        crate::diagnostics::clear_failures();
+        let mut events = crate::fetch_events_in_range(&self.client, &headers, user, since..=until).await
+            .map_err(|e| {
+                let status = if e.is::<crate::UserNotFound>() { StatusCode::NOT_FOUND } else { StatusCode::BAD_GATEWAY };
+                ApiError::new(status, format!("{:#}", e))
+            })?;
+        crate::sort_events(&mut events, crate::SortOrder::TimeDesc);
+        crate::resolve_repositories(&self.client, &headers, &self.repo_cache, &mut events, self.lookup_concurrency).await
+            .map_err(|e| ApiError::new(StatusCode::BAD_GATEWAY, format!("{:#}", e)))?;
+
+        // Drop expired entries, so the map doesn't grow with every range ever asked for
+        let ttl = self.ttl;
+        self.events.retain(|_, cached| cached.fetched_at.elapsed() < ttl);
+        let events = Arc::new(events);
+        self.events.insert(key, CachedEvents { fetched_at: Instant::now(), events: events.clone() });
+        Ok(events)
+    }
+
+    async fn respond(&mut self, request: &Request<Body>) -> Result<String, ApiError> {
+        if request.method() != Method::GET {
+            return Err(ApiError::new(StatusCode::METHOD_NOT_ALLOWED, "Only GET is supported"));
+        }
+        let path = request.uri().path();
+        if path != "/events" && path != "/summary" {
+            return Err(ApiError::new(StatusCode::NOT_FOUND, "Unknown endpoint; try /events or /summary"));
+        }
+
+        let params = query_params(request);
+        let user = params.get("user").filter(|u| !u.is_empty()).or(self.default_user.as_ref()).cloned()
+            .ok_or_else(|| ApiError::new(StatusCode::BAD_REQUEST,
+                "No user given; pass ?user= or start the server with a GitHub token"))?;
+        let time = params.get("time").map_or("30d", String::as_str);
+        let events = self.events(&user, time).await?;
+
+        if path == "/events" {
+            return crate::output::render_json(&events, None, true)
+                .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()));
+        }
+        let top = match params.get("top") {
+            Some(top) => top.parse().map_err(|_| ApiError::new(StatusCode::BAD_REQUEST, "top must be a number"))?,
+            None => summary::TOP_REPOS,
+        };
+        let summary = Summary::from_events(&events, self.timezone, top);
+        serde_json::to_string_pretty(&summary)
+            .map(|json| format!("{}\n", json))
+            .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+    }
+
+    async fn handle(&mut self, request: Request<Body>) -> Response<Body> {
+        match self.respond(&request).await {
+            Ok(body) => json_response(StatusCode::OK, body),
+            Err(e) => {
+                warn!("{} {}: {}", request.method(), request.uri(), e.message);
+                json_response(e.status, format!("{}\n", serde_json::json!({ "error": e.message })))
+            }
+        }
+    }
+}
+
+pub async fn run(args: &ServeArgs, options: &crate::RunOptions) -> Result<()> {
+    let (client, headers) = crate::setup_github_client()?;
+    let default_user = crate::get_authenticated_user(&client, &headers).await?;
+    let mut state = State {
+        client,
+        headers,
+        repo_cache: Arc::new(RwLock::new(crate::cache::load_repositories())),
+        default_user,
+        ttl: Duration::from_secs(args.cache_ttl),
+        timezone: args.timezone,
+        lookup_concurrency: options.lookup_concurrency,
+        max_range: options.max_range.clone(),
+        events: HashMap::new(),
+    };
+
+    // Connections are served on spawned tasks, which the fetching code can't
+    // run on, so they hand requests to this task and wait for the response.
+    // Handling one request at a time also means a burst of dashboard
+    // refreshes costs a single round of API calls.
+    let (sender, mut receiver) = mpsc::channel::<(Request<Body>, oneshot::Sender<Response<Body>>)>(64);
+    let make_service = make_service_fn(move |_| {
+        let sender = sender.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let sender = sender.clone();
+                async move {
+                    let (reply, response) = oneshot::channel();
+                    let unavailable = || json_response(StatusCode::SERVICE_UNAVAILABLE,
+                        format!("{}\n", serde_json::json!({ "error": "Server is shutting down" })));
+                    if sender.send((request, reply)).await.is_err() {
+                        return Ok::<_, Infallible>(unavailable());
+                    }
+                    Ok(response.await.unwrap_or_else(|_| unavailable()))
+                }
+            }))
+        }
+    });
+
+    let addr = SocketAddr::new(args.bind, args.port);
+    let server = Server::try_bind(&addr)
+        .context(format!("Failed to listen on {}", addr))?
+        .serve(make_service);
+    println!("Serving on http://{} (GET /events, /summary); press Ctrl-C to stop", addr);
+    let handler = async {
+        while let Some((request, reply)) = receiver.recv().await {
+            let _ = reply.send(state.handle(request).await);
+        }
+    };
+    tokio::select! {
+        result = server => result.context("Server error")?,
+        _ = handler => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+
+    Ok(())
+}
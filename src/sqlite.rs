@@ -0,0 +1,82 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::path::Path;
+
+use crate::Event;
+
+/// Bump when the table layout changes, and migrate older files in `open`
+const SCHEMA_VERSION: i64 = 1;
+
+/// Stable 64-bit FNV-1a hash, used to key events that have no GitHub id
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn event_id(event: &Event) -> String {
+    match &event.id {
+        Some(id) => id.clone(),
+        None => format!("git-{:016x}", fnv1a(&event.dedup_key())),
+    }
+}
+
+fn open(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path)
+        .context(format!("Failed to open database {}", path.display()))?;
+
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > SCHEMA_VERSION {
+        anyhow::bail!(
+            "Database {} uses schema version {}, newer than this wiwo supports ({})",
+            path.display(), version, SCHEMA_VERSION
+        );
+    }
+
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS events (
+            id TEXT PRIMARY KEY,
+            type TEXT NOT NULL,
+            repo TEXT NOT NULL,
+            private INTEGER,
+            url TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS events_created_at ON events (created_at);
+        PRAGMA user_version = {};",
+        SCHEMA_VERSION
+    ))?;
+
+    Ok(conn)
+}
+
+/// Upsert events into the `events` table so repeated runs accumulate history
+pub fn export(path: &Path, events: &[Event]) -> Result<usize> {
+    let mut conn = open(path)?;
+    let tx = conn.transaction()?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO events (id, type, repo, private, url, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(id) DO UPDATE SET
+                type = excluded.type,
+                repo = excluded.repo,
+                private = excluded.private,
+                url = excluded.url,
+                created_at = excluded.created_at",
+        )?;
+        for event in events {
+            stmt.execute(params![
+                event_id(event),
+                event.event_type,
+                event.repo.name,
+                event.repo.private,
+                event.repo.html_url(),
+                event.created_at.to_rfc3339(),
+            ])?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(events.len())
+}
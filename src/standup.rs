@@ -0,0 +1,96 @@
+use anyhow::Result;
+use chrono::{Datelike, Duration, Utc, Weekday};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::timezone::{self, DisplayZone};
+use crate::Event;
+
+#[derive(clap::Args)]
+pub struct StandupArgs {
+    /// GitHub username (defaults to authenticated user if GH_TOKEN is set)
+    #[arg(short, long)]
+    user: Option<String>,
+    /// Number of previous days to cover, up to a year (defaults to 1, or 3 on Mondays to include Friday)
+    #[arg(short, long, value_parser = clap::value_parser!(i64).range(1..=366))]
+    days: Option<i64>,
+    /// Time zone for deciding where days start: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+/// Describe `count` events of one type, e.g. "worked on 3 PRs"
+pub fn describe(formatted_type: &str, count: usize) -> String {
+    let plural = |one: &str, many: &str| if count == 1 { one.to_string() } else { many.to_string() };
+    match formatted_type {
+        "Push" => format!("pushed {} {}", count, plural("commit", "commits")),
+        "PR" => format!("worked on {} {}", count, plural("PR", "PRs")),
+        "PR Review" => format!("reviewed {} {}", count, plural("PR", "PRs")),
+        "PR Comment" => format!("left {} review {}", count, plural("comment", "comments")),
+        "Issues" => format!("worked on {} {}", count, plural("issue", "issues")),
+        "Issue Cmt" => format!("left {} issue {}", count, plural("comment", "comments")),
+        "Create" => format!("created {} {}", count, plural("branch or tag", "branches or tags")),
+        "Delete" => format!("deleted {} {}", count, plural("branch or tag", "branches or tags")),
+        "Release" => format!("published {} {}", count, plural("release", "releases")),
+        other => format!("{} {} {}", count, other, plural("event", "events")),
+    }
+}
+
+/// Markdown bullets per repository, collapsing events of the same type
+fn render(events: &[Event], title: &str) -> String {
+    let mut out = String::new();
+    writeln!(out, "*{}*", title).unwrap();
+
+    if events.is_empty() {
+        writeln!(out, "- No GitHub activity").unwrap();
+        return out;
+    }
+
+    let mut repos: BTreeMap<&str, (String, BTreeMap<String, usize>)> = BTreeMap::new();
+    for event in events {
+        let (_, counts) = repos.entry(&event.repo.name)
+            .or_insert_with(|| (event.repo.html_url(), BTreeMap::new()));
+        // Pushes count their commits where the payload says how many
+        let weight = if event.formatted_type() == "Push" { event.payload.size.unwrap_or(1) } else { 1 };
+        *counts.entry(event.formatted_type()).or_default() += weight;
+    }
+
+    for (name, (url, counts)) in &repos {
+        writeln!(out, "- [{}]({})", name, url).unwrap();
+        for (formatted_type, count) in counts {
+            writeln!(out, "  - {}", describe(formatted_type, *count)).unwrap();
+        }
+    }
+
+    out
+}
+
+pub async fn run(args: &StandupArgs) -> Result<()> {
+    let today = args.timezone.naive_local(Utc::now()).date();
+    let days = match args.days {
+        Some(days) => days,
+        // Cover Friday through Sunday after a weekend
+        None if today.weekday() == Weekday::Mon => 3,
+        None => 1,
+    };
+
+    let midnight = today.and_hms_opt(0, 0, 0).unwrap();
+    let since = args.timezone.to_utc(midnight - Duration::days(days));
+    let until = args.timezone.to_utc(midnight);
+
+    let (client, headers) = crate::setup_github_client()?;
+    let username = crate::resolve_username(&client, &headers, args.user.as_deref()).await?;
+
+    let mut events = crate::fetch_events_in_range(&client, &headers, &username, since..until).await?;
+    events.sort_by_key(|e| std::cmp::Reverse(e.created_at));
+
+    let first_day = today - Duration::days(days);
+    let title = if days == 1 {
+        format!("Standup for {}", first_day.format("%A %Y-%m-%d"))
+    } else {
+        format!("Standup for {} to {}", first_day.format("%A %Y-%m-%d"), (today - Duration::days(1)).format("%A %Y-%m-%d"))
+    };
+    print!("{}", render(&events, &title));
+
+    Ok(())
+}
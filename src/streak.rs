@@ -0,0 +1,135 @@
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::timezone::{self, DisplayZone};
+
+#[derive(clap::Args)]
+pub struct StreakArgs {
+    /// GitHub username (defaults to authenticated user if GH_TOKEN is set)
+    #[arg(short, long)]
+    user: Option<String>,
+    /// Time range to look for streaks in (e.g., "90d", "this-week")
+    #[arg(short, long, default_value = "90d", value_parser = crate::parse_time_arg)]
+    time: String,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = StreakFormat::Text)]
+    format: StreakFormat,
+    /// Time zone for deciding where days start: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StreakFormat {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Streak {
+    days: usize,
+    start: NaiveDate,
+    end: NaiveDate,
+}
+
+#[derive(Debug, Serialize)]
+struct DayCount {
+    date: NaiveDate,
+    count: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Streaks {
+    /// Consecutive active days up to today, or up to yesterday when today has
+    /// no activity yet
+    current: Option<Streak>,
+    longest: Option<Streak>,
+    active_days: usize,
+    /// Every day in the range, including those without events
+    days: Vec<DayCount>,
+}
+
+impl Streaks {
+    /// Streaks over the days from `first` to `last` inclusive, given event
+    /// counts per local day
+    fn from_counts(counts: &BTreeMap<NaiveDate, usize>, first: NaiveDate, last: NaiveDate) -> Self {
+        let days: Vec<DayCount> = first.iter_days()
+            .take_while(|date| *date <= last)
+            .map(|date| DayCount { date, count: counts.get(&date).copied().unwrap_or(0) })
+            .collect();
+
+        // Every maximal run of active days
+        let mut runs: Vec<Streak> = Vec::new();
+        for day in days.iter().filter(|d| d.count > 0) {
+            match runs.last_mut() {
+                Some(run) if run.end + Duration::days(1) == day.date => {
+                    run.end = day.date;
+                    run.days += 1;
+                }
+                _ => runs.push(Streak { days: 1, start: day.date, end: day.date }),
+            }
+        }
+
+        let active_days = runs.iter().map(|run| run.days).sum();
+        // The earliest of equally long runs
+        let longest = runs.iter()
+            .rev()
+            .max_by_key(|run| run.days)
+            .cloned();
+        let current = runs.pop().filter(|run| run.end >= last - Duration::days(1));
+
+        Streaks { current, longest, active_days, days }
+    }
+
+    fn render_text(&self, username: &str, time_range: &str) -> String {
+        let plural = |n: usize| if n == 1 { "day" } else { "days" };
+        let mut out = String::new();
+        writeln!(out, "Streaks for {} ({})\n", username, time_range).unwrap();
+        match &self.current {
+            Some(streak) => writeln!(out, "Current streak: {} {} (since {})", streak.days, plural(streak.days), streak.start),
+            None => writeln!(out, "Current streak: none"),
+        }.unwrap();
+        match &self.longest {
+            Some(streak) => writeln!(out, "Longest streak: {} {} ({} to {})", streak.days, plural(streak.days), streak.start, streak.end),
+            None => writeln!(out, "Longest streak: none"),
+        }.unwrap();
+        writeln!(out, "Active days: {} of {}", self.active_days, self.days.len()).unwrap();
+        out
+    }
+}
+
+pub async fn run(args: &StreakArgs) -> Result<()> {
+    let now = Utc::now();
+    let (since, until) = crate::parse_time_range(&args.time, now, args.timezone)?;
+    let until = until.unwrap_or(now);
+
+    // Activity from before the Events API window can't be seen here
+    let api_window = Duration::days(90);
+    if now - since > api_window {
+        warn!("Note: The GitHub Events API only covers the last 90 days, so earlier streaks are cut off; \
+            `wiwo events --time {}` also reads git history for older commits", args.time);
+    }
+
+    let (client, headers) = crate::setup_github_client()?;
+    let username = crate::resolve_username(&client, &headers, args.user.as_deref()).await?;
+
+    let events = crate::fetch_events_in_range(&client, &headers, &username, since..=until).await?;
+
+    let mut counts: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for event in &events {
+        *counts.entry(args.timezone.naive_local(event.created_at).date()).or_default() += 1;
+    }
+    let first = args.timezone.naive_local(since).date();
+    let last = args.timezone.naive_local(until).date();
+    let streaks = Streaks::from_counts(&counts, first, last);
+
+    match args.format {
+        StreakFormat::Text => print!("{}", streaks.render_text(&username, &args.time)),
+        StreakFormat::Json => println!("{}", serde_json::to_string_pretty(&streaks)?),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,288 @@
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::timezone::{self, DisplayZone};
+use crate::Event;
+
+/// Number of repositories listed in the summary by default
+pub const TOP_REPOS: usize = 5;
+
+#[derive(clap::Args)]
+pub struct SummaryArgs {
+    /// GitHub username (defaults to authenticated user if GH_TOKEN is set)
+    #[arg(short, long)]
+    user: Option<String>,
+    /// Time range to summarize (e.g., "30d", "1w", "yesterday"); limited to what the Events API returns
+    #[arg(short, long, default_value = "30d", value_parser = crate::parse_time_arg)]
+    time: String,
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = SummaryFormat::Text)]
+    format: SummaryFormat,
+    /// Number of repositories to list
+    #[arg(long, default_value_t = TOP_REPOS)]
+    top: usize,
+    /// Time zone for deciding where days start: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SummaryFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub total: usize,
+    pub by_type: Vec<TypeCount>,
+    pub top_repos: Vec<RepoCount>,
+    /// Events per user, when events from several users were combined
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub by_user: Vec<UserCount>,
+    /// Local day with the most events, the earliest on a tie
+    pub busiest_day: Option<DayCount>,
+    /// Whether `--limit` cut off older matching events
+    pub truncated: bool,
+    /// Repositories left out for having fewer than `--min-events` events
+    pub hidden_repos: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TypeCount {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepoCount {
+    pub repo: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserCount {
+    pub user: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayCount {
+    pub date: NaiveDate,
+    pub count: usize,
+}
+
+/// Count occurrences, most frequent first with ties broken by name
+fn ranked<'a>(keys: impl Iterator<Item = &'a str>) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for key in keys {
+        *counts.entry(key).or_default() += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+impl Summary {
+    /// Aggregate the events as displayed, i.e. after dedup, filtering and
+    /// `--limit`, listing the `top` most active repositories and bucketing
+    /// days in `timezone`. The caller fills in what was left out along the way.
+    pub fn from_events(events: &[Event], timezone: DisplayZone, top: usize) -> Self {
+        let types: Vec<String> = events.iter().map(|e| e.formatted_type()).collect();
+        let by_type = ranked(types.iter().map(String::as_str))
+            .into_iter()
+            .map(|(event_type, count)| TypeCount { event_type, count })
+            .collect();
+
+        let top_repos = ranked(events.iter().map(|e| e.repo.name.as_str()))
+            .into_iter()
+            .take(top)
+            .map(|(repo, count)| RepoCount { repo, count })
+            .collect();
+
+        let mut by_user: Vec<UserCount> = ranked(events.iter().filter_map(|e| e.user.as_deref()))
+            .into_iter()
+            .map(|(user, count)| UserCount { user, count })
+            .collect();
+        if by_user.len() < 2 {
+            by_user.clear();
+        }
+
+        let days: Vec<String> = events.iter()
+            .map(|e| timezone.naive_local(e.created_at).date().to_string())
+            .collect();
+        let busiest_day = ranked(days.iter().map(String::as_str))
+            .into_iter()
+            .next()
+            .map(|(date, count)| DayCount { date: date.parse().unwrap(), count });
+
+        Summary { total: events.len(), by_type, top_repos, by_user, busiest_day, truncated: false, hidden_repos: 0 }
+    }
+
+    /// Plain-text footer shown after the table
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        let noun = if self.total == 1 { "event" } else { "events" };
+        writeln!(out, "\nTotal: {} {}", self.total, noun).unwrap();
+        if self.truncated {
+            writeln!(out, "Output truncated to the {} most recent {} by --limit", self.total, noun).unwrap();
+        }
+        if let Some(day) = &self.busiest_day {
+            writeln!(out, "Busiest day: {} ({} {})", day.date.format("%a %Y-%m-%d"), day.count,
+                if day.count == 1 { "event" } else { "events" }).unwrap();
+        }
+        if self.hidden_repos > 0 {
+            let repos = if self.hidden_repos == 1 { "repository" } else { "repositories" };
+            writeln!(out, "Hidden by --min-events: {} {}", self.hidden_repos, repos).unwrap();
+        }
+
+        if !self.by_user.is_empty() {
+            let users: Vec<String> = self.by_user.iter()
+                .map(|u| format!("{} {}", u.user, u.count))
+                .collect();
+            writeln!(out, "By user: {}", users.join(", ")).unwrap();
+        }
+
+        if !self.by_type.is_empty() {
+            let types: Vec<String> = self.by_type.iter()
+                .map(|t| format!("{} {}", t.event_type, t.count))
+                .collect();
+            writeln!(out, "By type: {}", types.join(", ")).unwrap();
+        }
+
+        if !self.top_repos.is_empty() {
+            let width = self.top_repos.iter().map(|r| r.repo.len()).max().unwrap_or(0);
+            writeln!(out, "Top repositories:").unwrap();
+            for repo in &self.top_repos {
+                writeln!(out, "  {:width$}  {}", repo.repo, repo.count, width = width).unwrap();
+            }
+        }
+
+        out
+    }
+}
+
+/// Two-column table with a header, the counts right-aligned
+fn count_table(out: &mut String, header: &str, rows: &[(&str, usize)]) {
+    let width = rows.iter().map(|(name, _)| name.len()).chain([header.len()]).max().unwrap_or(0);
+    let count_width = rows.iter().map(|(_, count)| count.to_string().len()).chain([5]).max().unwrap_or(0);
+    writeln!(out, "{:width$}  {:>count_width$}", header, "COUNT", width = width, count_width = count_width).unwrap();
+    for (name, count) in rows {
+        writeln!(out, "{:width$}  {:>count_width$}", name, count, width = width, count_width = count_width).unwrap();
+    }
+}
+
+/// Report printed by the `summary` subcommand
+fn render_report(summary: &Summary, username: &str, time_range: &str) -> String {
+    let mut out = String::new();
+    writeln!(out, "Summary for {} ({})", username, time_range).unwrap();
+    let noun = if summary.total == 1 { "event" } else { "events" };
+    writeln!(out, "\nTotal: {} {}", summary.total, noun).unwrap();
+    if let Some(day) = &summary.busiest_day {
+        writeln!(out, "Busiest day: {} ({})", day.date.format("%a %Y-%m-%d"), day.count).unwrap();
+    }
+
+    if !summary.by_type.is_empty() {
+        writeln!(out).unwrap();
+        let rows: Vec<(&str, usize)> = summary.by_type.iter().map(|t| (t.event_type.as_str(), t.count)).collect();
+        count_table(&mut out, "TYPE", &rows);
+    }
+    if !summary.top_repos.is_empty() {
+        writeln!(out).unwrap();
+        let rows: Vec<(&str, usize)> = summary.top_repos.iter().map(|r| (r.repo.as_str(), r.count)).collect();
+        count_table(&mut out, "REPOSITORY", &rows);
+    }
+
+    out
+}
+
+pub async fn run(args: &SummaryArgs) -> Result<()> {
+    let now = Utc::now();
+    let (since, until) = crate::parse_time_range(&args.time, now, args.timezone)?;
+    let until = until.unwrap_or(now);
+
+    let (client, headers) = crate::setup_github_client()?;
+    let username = crate::resolve_username(&client, &headers, args.user.as_deref()).await?;
+
+    let events = crate::fetch_events_in_range(&client, &headers, &username, since..=until).await?;
+
+    let summary = Summary::from_events(&events, args.timezone, args.top);
+    match args.format {
+        SummaryFormat::Text => print!("{}", render_report(&summary, &username, &args.time)),
+        SummaryFormat::Json => println!("{}", serde_json::to_string_pretty(&summary)?),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Events of `event_type` in `repo`, one at each of `times`
+    fn events(spec: &[(&str, &str, &[&str])]) -> Vec<Event> {
+        let events: Vec<serde_json::Value> = spec.iter()
+            .flat_map(|(event_type, repo, times)| times.iter().map(move |time| serde_json::json!({
+                "type": event_type, "created_at": time, "repo": { "name": repo }
+            })))
+            .collect();
+        serde_json::from_value(serde_json::Value::Array(events)).unwrap()
+    }
+
+    fn utc() -> DisplayZone {
+        DisplayZone::Named(chrono_tz::UTC)
+    }
+
+    #[test]
+    fn counts_events_per_type() {
+        let events = events(&[
+            ("PushEvent", "a/x", &["2025-03-01T09:00:00Z", "2025-03-02T09:00:00Z", "2025-03-03T09:00:00Z"]),
+            ("PullRequestEvent", "a/x", &["2025-03-01T10:00:00Z"]),
+            ("IssuesEvent", "a/y", &["2025-03-01T11:00:00Z"]),
+        ]);
+        let summary = Summary::from_events(&events, utc(), TOP_REPOS);
+        assert_eq!(summary.total, 5);
+        let by_type: Vec<(&str, usize)> = summary.by_type.iter().map(|t| (t.event_type.as_str(), t.count)).collect();
+        // Ties by name
+        assert_eq!(by_type, [("Push", 3), ("Issues", 1), ("PR", 1)]);
+        assert!(summary.by_user.is_empty());
+    }
+
+    #[test]
+    fn lists_the_top_repositories_most_active_first() {
+        let events = events(&[
+            ("PushEvent", "a/quiet", &["2025-03-01T09:00:00Z"]),
+            ("PushEvent", "a/busy", &["2025-03-01T09:00:00Z", "2025-03-02T09:00:00Z", "2025-03-03T09:00:00Z"]),
+            ("PushEvent", "a/middle", &["2025-03-01T09:00:00Z", "2025-03-02T09:00:00Z"]),
+            ("PushEvent", "a/also-middle", &["2025-03-01T09:00:00Z", "2025-03-02T09:00:00Z"]),
+        ]);
+        let summary = Summary::from_events(&events, utc(), 3);
+        let top: Vec<(&str, usize)> = summary.top_repos.iter().map(|r| (r.repo.as_str(), r.count)).collect();
+        assert_eq!(top, [("a/busy", 3), ("a/also-middle", 2), ("a/middle", 2)]);
+        assert!(Summary::from_events(&events, utc(), 0).top_repos.is_empty());
+        assert_eq!(Summary::from_events(&events, utc(), 10).top_repos.len(), 4);
+    }
+
+    #[test]
+    fn busiest_day_is_the_earliest_on_a_tie_in_the_given_zone() {
+        // In UTC two events each on the 1st and 2nd and one on the 3rd; in Tokyo,
+        // nine hours ahead, the evening ones move a day on
+        let events = events(&[
+            ("PushEvent", "a/x", &[
+                "2025-03-01T09:00:00Z", "2025-03-01T20:00:00Z",
+                "2025-03-02T09:00:00Z", "2025-03-02T20:00:00Z",
+                "2025-03-03T09:00:00Z",
+            ]),
+        ]);
+        let busiest = Summary::from_events(&events, utc(), TOP_REPOS).busiest_day.unwrap();
+        assert_eq!((busiest.date.to_string(), busiest.count), ("2025-03-01".to_string(), 2));
+        let tokyo = DisplayZone::Named(chrono_tz::Asia::Tokyo);
+        let busiest = Summary::from_events(&events, tokyo, TOP_REPOS).busiest_day.unwrap();
+        assert_eq!((busiest.date.to_string(), busiest.count), ("2025-03-02".to_string(), 2));
+        assert!(Summary::from_events(&[], tokyo, TOP_REPOS).busiest_day.is_none());
+    }
+}
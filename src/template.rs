@@ -0,0 +1,61 @@
+use anyhow::Result;
+use handlebars::Handlebars;
+use serde_json::json;
+
+use crate::Event;
+
+const TEMPLATE_NAME: &str = "event";
+
+/// A user-supplied handlebars template rendered once per event
+pub struct EventTemplate {
+    registry: Handlebars<'static>,
+}
+
+impl EventTemplate {
+    pub fn compile(source: &str) -> Result<Self> {
+        let mut registry = Handlebars::new();
+        registry.register_escape_fn(handlebars::no_escape);
+
+        if let Err(e) = registry.register_template_string(TEMPLATE_NAME, source) {
+            match e.pos() {
+                Some((line, column)) => {
+                    anyhow::bail!("Template error at line {}, column {}: {}", line, column, e.reason())
+                }
+                None => anyhow::bail!("Template error: {}", e.reason()),
+            }
+        }
+
+        Ok(Self { registry })
+    }
+
+    pub fn render(&self, events: &[Event]) -> Result<String> {
+        let mut out = String::new();
+
+        for event in events {
+            let data = json!({
+                "id": event.id,
+                "created_at": event.created_at.to_rfc3339(),
+                "event_type": event.event_type,
+                "formatted_type": event.formatted_type(),
+                "actor": event.actor_login(),
+                "user": event.user,
+                "details": event.details(),
+                "repo": {
+                    "name": event.repo.name,
+                    "html_url": event.repo.html_url(),
+                    "private": event.repo.private,
+                },
+                "visibility": crate::output::visibility(event),
+            });
+
+            let rendered = self.registry.render(TEMPLATE_NAME, &data)?;
+            out.push_str(&rendered);
+            // One-line templates rarely include their own newline
+            if !rendered.ends_with('\n') {
+                out.push('\n');
+            }
+        }
+
+        Ok(out)
+    }
+}
@@ -0,0 +1,52 @@
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+
+/// Paths and queries requested so far, in order
+pub type Requests = Arc<Mutex<Vec<String>>>;
+
+/// A response with the rate limit headers GitHub sends with every one
+pub fn response(status: StatusCode, body: &str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("x-ratelimit-remaining", "4999")
+        .header("x-ratelimit-reset", "0")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+/// Serve `respond`'s answers on a local port, standing in for the GitHub API;
+/// requests it answers with `None` never get a response, like a stalled
+/// endpoint. Returns the base URL and the requests made. Caching is turned
+/// off, so tests neither read nor write the real cache.
+pub fn start<F>(respond: F) -> (String, Requests)
+where
+    F: Fn(&Request<Body>) -> Option<Response<Body>> + Send + Sync + 'static,
+{
+    crate::cache::set_enabled(false);
+    let respond = Arc::new(respond);
+    let requests = Requests::default();
+    let log = requests.clone();
+    let make_service = make_service_fn(move |_| {
+        let (respond, log) = (respond.clone(), log.clone());
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let path = request.uri().path_and_query().map_or_else(String::new, ToString::to_string);
+                log.lock().unwrap().push(path);
+                let response = respond(&request);
+                async move {
+                    match response {
+                        Some(response) => Ok::<_, Infallible>(response),
+                        None => std::future::pending().await,
+                    }
+                }
+            }))
+        }
+    });
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+    let url = format!("http://{}", server.local_addr());
+    tokio::spawn(server);
+    (url, requests)
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, LocalResult, NaiveDateTime, TimeZone, Utc};
+
+/// Time zone used when displaying timestamps; all computation stays in UTC
+#[derive(Clone, Copy, Debug)]
+pub enum DisplayZone {
+    Local,
+    Named(chrono_tz::Tz),
+}
+
+impl DisplayZone {
+    /// Format a UTC timestamp in this zone
+    pub fn format(&self, time: DateTime<Utc>, fmt: &str) -> String {
+        match self {
+            DisplayZone::Local => time.with_timezone(&chrono::Local).format(fmt).to_string(),
+            DisplayZone::Named(tz) => time.with_timezone(tz).format(fmt).to_string(),
+        }
+    }
+
+    pub fn format_rfc3339(&self, time: DateTime<Utc>) -> String {
+        match self {
+            DisplayZone::Local => time.with_timezone(&chrono::Local).to_rfc3339(),
+            DisplayZone::Named(tz) => time.with_timezone(tz).to_rfc3339(),
+        }
+    }
+
+    /// The UTC instant of a wall-clock time in this zone, taking the earlier
+    /// one when it is ambiguous (DST fall-back) and the later when skipped
+    pub fn to_utc(self, local: NaiveDateTime) -> DateTime<Utc> {
+        fn resolve<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> DateTime<Utc> {
+            match tz.from_local_datetime(&local) {
+                LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => t.with_timezone(&Utc),
+                LocalResult::None => resolve(tz, local + chrono::Duration::hours(1)),
+            }
+        }
+        match self {
+            DisplayZone::Local => resolve(&chrono::Local, local),
+            DisplayZone::Named(tz) => resolve(&tz, local),
+        }
+    }
+
+    /// Wall-clock time in this zone, for bucketing by local day or hour
+    pub fn naive_local(&self, time: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            DisplayZone::Local => time.with_timezone(&chrono::Local).naive_local(),
+            DisplayZone::Named(tz) => time.with_timezone(tz).naive_local(),
+        }
+    }
+}
+
+/// Parse `local` or an IANA zone name such as `Europe/Berlin`
+pub fn parse_timezone(s: &str) -> Result<DisplayZone, String> {
+    if s.eq_ignore_ascii_case("local") {
+        return Ok(DisplayZone::Local);
+    }
+    s.parse::<chrono_tz::Tz>()
+        .map(DisplayZone::Named)
+        .map_err(|_| format!("unknown time zone '{}'; use an IANA name like 'Europe/Berlin' or 'local'", s))
+}
@@ -0,0 +1,309 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use ratatui::crossterm::event::{self as term, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::Style;
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use unicode_width::UnicodeWidthStr;
+
+use crate::output::{Column, RenderContext, TableStyle, TimeFormat};
+use crate::timezone::{self, DisplayZone};
+use crate::Event;
+
+const SPINNER: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+#[derive(clap::Args)]
+pub struct TuiArgs {
+    /// GitHub username (defaults to authenticated user if GH_TOKEN is set)
+    #[arg(short, long)]
+    user: Option<String>,
+    /// Time range to browse (e.g., "30d", "1w", "yesterday")
+    #[arg(short, long, default_value = "30d", value_parser = crate::parse_time_arg)]
+    time: String,
+    /// Time zone for displaying times: an IANA name like "Europe/Berlin", or "local"
+    #[arg(long, value_parser = timezone::parse_timezone, default_value = "local")]
+    timezone: DisplayZone,
+}
+
+/// Result of the background fetch, handed to the UI thread: the resolved
+/// username and their events
+type Loaded = Result<(String, Vec<Event>), String>;
+
+struct App {
+    username: String,
+    time_range: String,
+    timezone: DisplayZone,
+    since: DateTime<Utc>,
+    /// `None` until the fetch finishes
+    events: Option<Vec<Event>>,
+    error: Option<String>,
+    /// Case-insensitive text matched against event types and repository names
+    filter: String,
+    /// Whether keys are typed into the filter rather than acting as commands
+    editing: bool,
+    table: TableState,
+    /// Feedback shown in the status line until the next key
+    message: Option<String>,
+    ticks: usize,
+}
+
+impl App {
+    fn visible(&self) -> Vec<&Event> {
+        let filter = self.filter.to_lowercase();
+        self.events.iter().flatten()
+            .filter(|e| filter.is_empty()
+                || e.formatted_type().to_lowercase().contains(&filter)
+                || e.event_type.to_lowercase().contains(&filter)
+                || e.repo.name.to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    fn select(&mut self, index: usize) {
+        let count = self.visible().len();
+        self.table.select((count > 0).then(|| index.min(count - 1)));
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let current = self.table.selected().unwrap_or(0);
+        self.select(current.saturating_add_signed(delta));
+    }
+
+    /// Apply a key press; returns whether to quit
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        self.message = None;
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return true;
+        }
+
+        if self.editing {
+            match key.code {
+                KeyCode::Char(c) => self.filter.push(c),
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Enter => self.editing = false,
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.editing = false;
+                }
+                _ => return false,
+            }
+            self.select(0);
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => return true,
+            KeyCode::Char('j') | KeyCode::Down => self.move_by(1),
+            KeyCode::Char('k') | KeyCode::Up => self.move_by(-1),
+            KeyCode::PageDown => self.move_by(10),
+            KeyCode::PageUp => self.move_by(-10),
+            KeyCode::Char('g') | KeyCode::Home => self.select(0),
+            KeyCode::Char('G') | KeyCode::End => self.select(usize::MAX),
+            KeyCode::Char('/') => self.editing = true,
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.select(0);
+            }
+            KeyCode::Char('o') => {
+                let url = self.table.selected()
+                    .and_then(|i| self.visible().get(i).map(|e| e.repo.html_url()));
+                if let Some(url) = url {
+                    self.message = Some(match open_in_browser(&url) {
+                        Ok(()) => format!("Opened {}", url),
+                        Err(e) => format!("Failed to open {}: {}", url, e),
+                    });
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [list_area, detail_area, status_area] = Layout::vertical([
+            Constraint::Percentage(60),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ]).areas(frame.area());
+
+        let context = RenderContext {
+            username: &self.username,
+            time_range: &self.time_range,
+            columns: Column::DEFAULT,
+            time_format: TimeFormat::Absolute,
+            timezone: self.timezone,
+            color: false,
+            max_width: None,
+            hyperlinks: false,
+            group_by: None,
+            summary: None,
+            since: self.since,
+            until: None,
+            sparkline: false,
+            table_style: TableStyle::Compact,
+            filtered: !self.filter.is_empty(),
+        };
+        let visible = self.visible();
+        let cells: Vec<Vec<String>> = visible.iter()
+            .map(|e| Column::DEFAULT.iter().map(|c| c.cell(e, &context)).collect())
+            .collect();
+        // Size columns to their contents, letting the last take what's left
+        let widths: Vec<Constraint> = Column::DEFAULT.iter().enumerate()
+            .map(|(i, column)| {
+                if i + 1 == Column::DEFAULT.len() {
+                    return Constraint::Fill(1);
+                }
+                let width = cells.iter().map(|row| row[i].width()).chain([column.header().len()]).max().unwrap_or(0);
+                Constraint::Length(width as u16)
+            })
+            .collect();
+
+        let title = format!(" {} ({}) — {} of {} events ", self.username, self.time_range,
+            visible.len(), self.events.as_ref().map_or(0, Vec::len));
+        let table = Table::new(cells.into_iter().map(Row::new), widths)
+            .header(Row::new(Column::DEFAULT.iter().map(|c| c.header())).style(Style::new().bold()))
+            .block(Block::bordered().title(title))
+            .row_highlight_style(Style::new().reversed());
+
+        let selected = self.table.selected().and_then(|i| visible.get(i).copied());
+        let detail = match selected {
+            Some(event) => {
+                let title = match event.details() {
+                    details if details.is_empty() => format!(" {} {} ", event.formatted_type(), event.repo.name),
+                    details => format!(" {} {}: {} ", event.formatted_type(), event.repo.name, details),
+                };
+                let payload = serde_json::to_string_pretty(&event.payload).unwrap_or_default();
+                Paragraph::new(payload).block(Block::bordered().title(title))
+            }
+            None => Paragraph::new("").block(Block::bordered().title(" Payload ")),
+        };
+
+        let status = if self.editing {
+            format!("/{}█", self.filter)
+        } else if let Some(message) = &self.message {
+            message.clone()
+        } else if let Some(error) = &self.error {
+            format!("Failed to load events: {}  (q to quit)", error)
+        } else if self.events.is_none() {
+            format!("{} Loading events for {}...", SPINNER[self.ticks % SPINNER.len()], self.username)
+        } else if !self.filter.is_empty() {
+            format!("Filter: {}  (/ to edit, Esc to clear)  j/k move  o open  q quit", self.filter)
+        } else {
+            "j/k move  / filter  o open repository  q quit".to_string()
+        };
+
+        frame.render_stateful_widget(table, list_area, &mut self.table);
+        frame.render_widget(detail, detail_area);
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal, loaded: Receiver<Loaded>) -> Result<()> {
+        loop {
+            if self.events.is_none() && self.error.is_none() {
+                match loaded.try_recv() {
+                    Ok(Ok((username, events))) => {
+                        self.username = username;
+                        self.events = Some(events);
+                        self.select(0);
+                    }
+                    Ok(Err(e)) => self.error = Some(e),
+                    Err(TryRecvError::Empty) => {}
+                    Err(TryRecvError::Disconnected) => self.error = Some("loading was interrupted".to_string()),
+                }
+            }
+
+            terminal.draw(|frame| self.draw(frame))?;
+
+            // Poll with a timeout so the spinner turns and loaded events show up
+            if term::poll(Duration::from_millis(100))?
+                && let term::Event::Key(key) = term::read()?
+                && key.kind == KeyEventKind::Press
+                && self.handle_key(key)
+            {
+                return Ok(());
+            }
+            self.ticks += 1;
+        }
+    }
+}
+
+fn open_in_browser(url: &str) -> std::io::Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let mut command = std::process::Command::new("xdg-open");
+
+    command.arg(url)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+async fn load_events(user: Option<&str>, since: DateTime<Utc>, until: DateTime<Utc>, lookup_concurrency: usize) -> Result<(String, Vec<Event>)> {
+    let (client, headers) = crate::setup_github_client()?;
+    let username = crate::resolve_username(&client, &headers, user).await?;
+
+    let mut events = crate::fetch_events_in_range(&client, &headers, &username, since..=until).await?;
+    crate::sort_events(&mut events, crate::SortOrder::TimeDesc);
+
+    // For the VISIBILITY column
+    let cache = Arc::new(RwLock::new(crate::cache::load_repositories()));
+    crate::resolve_repositories(&client, &headers, &cache, &mut events, lookup_concurrency).await?;
+    Ok((username, events))
+}
+
+pub async fn run(args: &TuiArgs, options: &crate::RunOptions) -> Result<()> {
+    let now = Utc::now();
+    let (since, until) = crate::parse_time_range(&args.time, now, args.timezone)?;
+    let until = until.unwrap_or(now);
+
+    // Warnings printed while fetching would scribble over the screen
+    crate::diagnostics::set_quiet(true);
+
+    let mut app = App {
+        username: args.user.clone().unwrap_or_else(|| "the authenticated user".to_string()),
+        time_range: args.time.clone(),
+        timezone: args.timezone,
+        since,
+        events: None,
+        error: None,
+        filter: String::new(),
+        editing: false,
+        table: TableState::default(),
+        message: None,
+        ticks: 0,
+    };
+
+    // The UI blocks on terminal input, so it gets its own thread while the
+    // fetch runs here. `ratatui::init` restores the terminal on panic, and raw
+    // mode turns Ctrl+C into a key press the UI quits on.
+    let (sender, receiver) = mpsc::channel();
+    let mut ui = tokio::task::spawn_blocking(move || {
+        let mut terminal = ratatui::init();
+        let result = app.run(&mut terminal, receiver);
+        ratatui::restore();
+        result
+    });
+
+    tokio::select! {
+        // Quit before the events arrived
+        result = &mut ui => return result.context("The UI thread panicked")?,
+        events = load_events(args.user.as_deref(), since, until, options.lookup_concurrency) => {
+            let _ = sender.send(events.map_err(|e| format!("{:#}", e)));
+        }
+    }
+    ui.await.context("The UI thread panicked")?
+}
@@ -0,0 +1,178 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_NONE_MATCH};
+use std::collections::HashSet;
+
+use crate::filter::EventFilter;
+use crate::net::SendRetrying;
+use crate::output::{Column, RenderContext, TableLayout};
+use crate::{Event, RepositoryCache};
+
+/// Longest wait between polls after repeated failures
+const MAX_BACKOFF_SECS: u64 = 900;
+
+/// What `events --watch` keeps polling for after the initial table
+pub struct Watch<'a> {
+    pub usernames: &'a [String],
+    pub filter: EventFilter,
+    /// Start of the requested range
+    pub since: DateTime<Utc>,
+    /// Seconds between polls, unless GitHub asks for longer
+    pub interval: u64,
+    pub lookup_concurrency: usize,
+}
+
+/// An events endpoint and what the last poll of it said
+struct Endpoint {
+    url: String,
+    username: String,
+    etag: Option<String>,
+    /// Minimum seconds between polls, from GitHub's X-Poll-Interval
+    poll_interval: u64,
+}
+
+enum Poll {
+    /// The first page changed since the last poll
+    Events(Vec<Event>),
+    /// Unchanged; conditional requests answered with 304 cost no rate limit quota
+    NotModified,
+    /// Rate limited; wait this many seconds before polling again
+    RateLimited(u64),
+}
+
+/// Fetch the first page of an endpoint, sending the ETag from the last poll
+async fn poll(client: &reqwest::Client, headers: &HeaderMap, endpoint: &mut Endpoint) -> Result<Poll> {
+    // A GitHub App's token can be replaced while watching
+    let mut request_headers = crate::current_headers(headers)?;
+    if let Some(etag) = &endpoint.etag
+        && let Ok(value) = HeaderValue::from_str(etag)
+    {
+        request_headers.insert(IF_NONE_MATCH, value);
+    }
+    let response = client
+        .get(format!("{}?per_page=100", endpoint.url))
+        .headers(request_headers)
+        .send_retrying()
+        .await
+        .context(format!("Failed to poll {}", endpoint.url))?;
+
+    crate::ratelimit::record(response.headers());
+    let status = response.status();
+    let header = |name: &str| response.headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<i64>().ok());
+    if let Some(interval) = header("x-poll-interval") {
+        endpoint.poll_interval = interval.max(0) as u64;
+    }
+
+    let rate_limited = status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        || (status == reqwest::StatusCode::FORBIDDEN
+            && (header("x-ratelimit-remaining") == Some(0) || header("retry-after").is_some()));
+    if rate_limited {
+        // Prefer Retry-After (secondary limits), else wait for the window to reset
+        let wait = header("retry-after")
+            .or_else(|| header("x-ratelimit-reset").map(|reset| reset - Utc::now().timestamp()))
+            .unwrap_or(60);
+        return Ok(Poll::RateLimited(wait.max(0) as u64 + 1));
+    }
+    if status == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(Poll::NotModified);
+    }
+    if !status.is_success() {
+        anyhow::bail!("Failed to poll {} ({})", endpoint.url, status);
+    }
+
+    endpoint.etag = response.headers()
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let mut events: Vec<Event> = response.json().await
+        .context(format!("Failed to parse events from {}", endpoint.url))?;
+    for event in &mut events {
+        event.user = Some(endpoint.username.clone());
+    }
+    Ok(Poll::Events(events))
+}
+
+async fn poll_forever(client: &reqwest::Client, headers: &HeaderMap, cache: &RepositoryCache, watch: &Watch<'_>, context: &RenderContext<'_>, initial: &[Event]) -> Result<()> {
+    let mut endpoints: Vec<Endpoint> = Vec::new();
+    for username in watch.usernames {
+        endpoints.extend(crate::event_endpoints(client, headers, username).await.into_iter().map(|url| Endpoint {
+            url,
+            username: username.clone(),
+            etag: None,
+            poll_interval: 0,
+        }));
+    }
+
+    // Events older than the newest one shown, e.g. those cut by --limit,
+    // aren't new; after that, anything with an unseen id is
+    let watermark = initial.iter().map(|e| e.created_at).max().unwrap_or(watch.since);
+    let mut seen: HashSet<(Option<String>, String)> = initial.iter()
+        .map(|e| (e.user.clone(), e.dedup_key()))
+        .collect();
+    let mut layout = (!initial.is_empty()).then(|| TableLayout::new(initial, context, None));
+    let mut failures = 0;
+
+    loop {
+        let mut wait = watch.interval;
+        let mut fresh: Vec<Event> = Vec::new();
+        let mut failed = false;
+        for endpoint in &mut endpoints {
+            match poll(client, headers, endpoint).await {
+                Ok(Poll::Events(events)) => fresh.extend(events),
+                Ok(Poll::NotModified) => {}
+                Ok(Poll::RateLimited(secs)) => {
+                    warn!("Rate limit reached. Waiting {} seconds...", secs);
+                    wait = wait.max(secs);
+                }
+                Err(e) => {
+                    warn!("Warning: {}", e);
+                    failed = true;
+                }
+            }
+            wait = wait.max(endpoint.poll_interval);
+        }
+
+        // Back off exponentially while polls keep failing
+        failures = if failed { failures + 1 } else { 0 };
+        if failures > 0 {
+            wait = wait.saturating_mul(1 << failures.min(10)).min(MAX_BACKOFF_SECS);
+        }
+
+        fresh.retain(|e| e.created_at >= watermark && seen.insert((e.user.clone(), e.dedup_key())));
+        if !fresh.is_empty() {
+            if context.columns.contains(&Column::Visibility) || watch.filter.needs_details() {
+                crate::resolve_repositories(client, headers, cache, &mut fresh, watch.lookup_concurrency).await?;
+            } else {
+                crate::fill_urls(&mut fresh);
+            }
+            fresh.retain(|e| watch.filter.matches(e));
+            // Like `tail -f`, the newest events go last
+            fresh.sort_by_key(|e| e.created_at);
+        }
+        if !fresh.is_empty() {
+            let layout = layout.get_or_insert_with(|| {
+                let layout = TableLayout::new(&fresh, context, None);
+                println!("{}", layout.header(context));
+                layout
+            });
+            for event in &fresh {
+                println!("{}", layout.row(event, context));
+            }
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(wait)).await;
+    }
+}
+
+/// Keep printing new events as table rows until interrupted with Ctrl+C
+pub async fn run(client: &reqwest::Client, headers: &HeaderMap, cache: &RepositoryCache, watch: Watch<'_>, context: &RenderContext<'_>, initial: &[Event]) -> Result<()> {
+    warn!("Watching for new events every {} seconds; press Ctrl+C to stop", watch.interval);
+    tokio::select! {
+        result = poll_forever(client, headers, cache, &watch, context, initial) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+